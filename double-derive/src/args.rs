@@ -0,0 +1,421 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Ident, LitBool, Path, Token, Type, meta::ParseNestedMeta, parse_quote};
+
+/// Arguments passed to the [`crate::dummies`] attribute, e.g. `#[dummies(crate = my_crate)]`.
+#[derive(Clone)]
+pub struct Args {
+    /// Path to the `double-trait` crate. All code generated by `dummies` refers to items of the
+    /// runtime crate through this path. Defaults to `double_trait`.
+    pub krate: Path,
+    /// The type for which the trait is implemented trivially.
+    pub dummy: DummyArg,
+    /// Implement `IntoDyn` for the dummy, so it can be converted into trait objects. Set via
+    /// `dyn_compatible`. Emits an error, if the trait is not dyn compatible.
+    pub dyn_compatible: bool,
+    /// Mention arguments and caller location in the panic messages of default implementations. Set
+    /// via `verbose`.
+    pub verbose: bool,
+    /// Invoked by default implementations which can not provide a sensible value. Set via
+    /// `on_unimplemented = todo!` or `on_unimplemented = my_handler`. Defaults to `unimplemented!`.
+    pub on_unimplemented: OnUnimplemented,
+    /// Whether default implementations panic more or less often than usual. Set via `strict` or
+    /// `lenient`. Can be overridden per method.
+    pub mode: Mode,
+    /// Document the generated default implementation of each method. Set via `doc`.
+    pub doc: bool,
+    /// Add a table summarizing the generated default implementations to the documentation of the
+    /// trait. Set via `doc_summary`.
+    pub doc_summary: bool,
+    /// Emit the expansion and the chosen default strategies as a compile time warning. Set via
+    /// `debug`.
+    pub debug: bool,
+    /// Leave the trait untouched and generate a macro instead, which allows `fill` to add the
+    /// default implementations to impls of the trait. Set via `fill`.
+    pub fill: bool,
+    /// Implement unsafe traits for the dummy, asserting that it upholds their safety contract. Set
+    /// via `allow_unsafe`.
+    pub allow_unsafe: bool,
+    /// What default implementations return for `Poll`. Set via `poll = pending` or `poll = ready`.
+    pub poll: PollArg,
+}
+
+/// Decides whether default implementations panic or return a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Return sensible values like `()`, `None` or `Ok(())` and panic otherwise.
+    Default,
+    /// Always panic, even if a sensible value could be returned. Set via `strict`.
+    Strict,
+    /// Additionally return `Default::default()` for types implementing `Default`. Only panics for
+    /// types for which no value can be constructed. Set via `lenient`.
+    Lenient,
+}
+
+/// The variant of `Poll` returned by default implementations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollArg {
+    /// Return `Poll::Pending`. This is the default.
+    Pending,
+    /// Return `Poll::Ready` of the default of the inner type.
+    Ready,
+}
+
+/// What default implementations invoke, if they can not provide a sensible value.
+#[derive(Clone)]
+pub enum OnUnimplemented {
+    /// A macro like `unimplemented!` or `todo!`, invoked with a format string.
+    Macro(Path),
+    /// A function like `fn handler<T>(message: &str) -> T`, invoked with the formatted message.
+    Function(Path),
+}
+
+/// Which type, if any, `dummies` implements the trait for.
+#[derive(Clone)]
+pub enum DummyArg {
+    /// Implement the trait for `double_trait::Dummy`. This is the default.
+    Shared,
+    /// Implement the trait for a user supplied type. Set via `dummy = MyDummy`.
+    Custom(Box<Type>),
+    /// Generate a dedicated unit struct for the trait and implement the trait for it. Set via
+    /// `dummy_struct` or `dummy_struct = MyTraitDummy`. The name defaults to the name of the trait
+    /// with a `Dummy` suffix.
+    Struct(Option<Ident>),
+    /// Do not implement the trait for any type. Set via `no_dummy`.
+    Skip,
+}
+
+impl Args {
+    /// Parses a single argument. Intended to be used with [`syn::meta::parser`].
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("crate") {
+            self.krate = meta.value()?.parse()?;
+            Ok(())
+        } else if meta.path.is_ident("dummy") {
+            self.set_dummy(DummyArg::Custom(Box::new(meta.value()?.parse()?)), &meta)
+        } else if meta.path.is_ident("dummy_struct") {
+            let name = if meta.input.peek(Token![=]) {
+                Some(meta.value()?.parse()?)
+            } else {
+                None
+            };
+            self.set_dummy(DummyArg::Struct(name), &meta)
+        } else if meta.path.is_ident("no_dummy") {
+            self.set_dummy(DummyArg::Skip, &meta)
+        } else if meta.path.is_ident("dyn_compatible") {
+            self.dyn_compatible = true;
+            Ok(())
+        } else if meta.path.is_ident("verbose") {
+            self.verbose = true;
+            Ok(())
+        } else if meta.path.is_ident("doc") {
+            self.doc = true;
+            Ok(())
+        } else if meta.path.is_ident("doc_summary") {
+            self.doc_summary = true;
+            Ok(())
+        } else if meta.path.is_ident("debug") {
+            self.debug = true;
+            Ok(())
+        } else if meta.path.is_ident("fill") {
+            self.fill = true;
+            Ok(())
+        } else if meta.path.is_ident("allow_unsafe") {
+            self.allow_unsafe = true;
+            Ok(())
+        } else if let Some(mode) = parse_mode(&meta)? {
+            if self.mode != Mode::Default && mode != Mode::Default {
+                return Err(meta.error("only one of `strict` and `lenient` may be specified"));
+            }
+            self.mode = mode;
+            Ok(())
+        } else if meta.path.is_ident("poll") {
+            let variant: Ident = meta.value()?.parse()?;
+            self.poll = if variant == "pending" {
+                PollArg::Pending
+            } else if variant == "ready" {
+                PollArg::Ready
+            } else {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected `pending` or `ready`",
+                ));
+            };
+            Ok(())
+        } else if meta.path.is_ident("on_unimplemented") {
+            let value = meta.value()?;
+            let path = value.parse()?;
+            self.on_unimplemented = if value.peek(Token![!]) {
+                value.parse::<Token![!]>()?;
+                OnUnimplemented::Macro(path)
+            } else {
+                OnUnimplemented::Function(path)
+            };
+            Ok(())
+        } else {
+            Err(meta.error("unsupported dummies argument"))
+        }
+    }
+
+    /// The type the trait should be implemented for. `None` if no implementation should be
+    /// generated.
+    pub fn dummy_type(&self, trait_name: &Ident) -> Option<Type> {
+        let krate = &self.krate;
+        match &self.dummy {
+            DummyArg::Shared => Some(parse_quote!(#krate::__private::Dummy)),
+            DummyArg::Custom(ty) => Some(Type::clone(ty)),
+            DummyArg::Struct(_) => {
+                let name = self.dummy_struct(trait_name)?;
+                Some(parse_quote!(#name))
+            }
+            DummyArg::Skip => None,
+        }
+    }
+
+    /// The arguments affecting the bodies of default implementations, in the same syntax they are
+    /// parsed from.
+    pub fn body_args(&self) -> TokenStream {
+        let krate = &self.krate;
+        let verbose = self.verbose.then(|| quote! { verbose, });
+        let on_unimplemented = match &self.on_unimplemented {
+            OnUnimplemented::Macro(path) => quote! { on_unimplemented = #path!, },
+            OnUnimplemented::Function(path) => quote! { on_unimplemented = #path, },
+        };
+        let mode = match self.mode {
+            Mode::Default => None,
+            Mode::Strict => Some(quote! { strict, }),
+            Mode::Lenient => Some(quote! { lenient, }),
+        };
+        let poll = match self.poll {
+            PollArg::Pending => None,
+            PollArg::Ready => Some(quote! { poll = ready, }),
+        };
+        quote! { crate = #krate, #verbose #on_unimplemented #mode #poll }
+    }
+
+    /// Name of the dedicated dummy struct, if one should be generated.
+    pub fn dummy_struct(&self, trait_name: &Ident) -> Option<Ident> {
+        let DummyArg::Struct(name) = &self.dummy else {
+            return None;
+        };
+        let name = name
+            .clone()
+            .unwrap_or_else(|| Ident::new(&format!("{trait_name}Dummy"), trait_name.span()));
+        Some(name)
+    }
+
+    fn set_dummy(&mut self, dummy: DummyArg, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if !matches!(self.dummy, DummyArg::Shared) {
+            return Err(
+                meta.error("only one of `dummy`, `dummy_struct` and `no_dummy` may be specified")
+            );
+        }
+        self.dummy = dummy;
+        Ok(())
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            krate: parse_quote!(double_trait),
+            dummy: DummyArg::Shared,
+            dyn_compatible: false,
+            verbose: false,
+            on_unimplemented: OnUnimplemented::Macro(parse_quote!(unimplemented)),
+            mode: Mode::Default,
+            doc: false,
+            doc_summary: false,
+            debug: false,
+            fill: false,
+            allow_unsafe: false,
+            poll: PollArg::Pending,
+        }
+    }
+}
+
+/// Arguments passed to a `#[dummies(...)]` attribute on an individual method of the annotated
+/// trait. These override the arguments passed to the trait.
+#[derive(Default)]
+pub struct MethodArgs {
+    /// Set via `strict`, `lenient`, `strict = false` or `lenient = false`.
+    pub mode: Option<Mode>,
+}
+
+impl MethodArgs {
+    /// Parses and removes all `#[dummies(...)]` attributes from a method.
+    pub fn extract(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut method_args = MethodArgs::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            let is_dummies = attr
+                .path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "dummies");
+            if is_dummies && result.is_ok() {
+                result = attr.parse_nested_meta(|meta| method_args.parse(meta));
+            }
+            !is_dummies
+        });
+        result.map(|()| method_args)
+    }
+
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if let Some(mode) = parse_mode(&meta)? {
+            self.mode = Some(mode);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported dummies argument for methods"))
+        }
+    }
+}
+
+/// Parses `strict` and `lenient`. `None` if the argument is neither of them.
+fn parse_mode(meta: &ParseNestedMeta) -> syn::Result<Option<Mode>> {
+    let mode = if meta.path.is_ident("strict") {
+        Mode::Strict
+    } else if meta.path.is_ident("lenient") {
+        Mode::Lenient
+    } else {
+        return Ok(None);
+    };
+    let mode = if parse_flag(meta)? {
+        mode
+    } else {
+        Mode::Default
+    };
+    Ok(Some(mode))
+}
+
+/// Flags may either be stated on their own, e.g. `strict`, or with an explicit value, e.g.
+/// `strict = false`.
+fn parse_flag(meta: &ParseNestedMeta) -> syn::Result<bool> {
+    if meta.input.peek(Token![=]) {
+        let value: LitBool = meta.value()?.parse()?;
+        Ok(value.value)
+    } else {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{parse::Parser, parse_quote};
+
+    use super::{Args, MethodArgs, Mode, OnUnimplemented, PollArg};
+
+    #[test]
+    fn crate_path_defaults_to_double_trait() {
+        let args = Args::default();
+        let krate = &args.krate;
+        assert_eq!("double_trait", quote! { #krate }.to_string());
+    }
+
+    #[test]
+    fn parse_crate_path() {
+        let args = given(quote! { crate = my_facade::double_trait }).unwrap();
+        let krate = &args.krate;
+        assert_eq!(
+            quote! { my_facade::double_trait }.to_string(),
+            quote! { #krate }.to_string()
+        );
+    }
+
+    #[test]
+    fn dummy_and_no_dummy_are_mutually_exclusive() {
+        let result = given(quote! { no_dummy, dummy = MyDummy });
+        let Err(error) = result else {
+            panic!("Expected error for conflicting arguments");
+        };
+        assert_eq!(
+            "only one of `dummy`, `dummy_struct` and `no_dummy` may be specified",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_on_unimplemented_macro_and_function() {
+        let args = given(quote! { on_unimplemented = todo! }).unwrap();
+        let OnUnimplemented::Macro(path) = args.on_unimplemented else {
+            panic!("Expected macro");
+        };
+        assert!(path.is_ident("todo"));
+
+        let args = given(quote! { on_unimplemented = my::handler }).unwrap();
+        let OnUnimplemented::Function(path) = args.on_unimplemented else {
+            panic!("Expected function");
+        };
+        assert_eq!(
+            quote! { my::handler }.to_string(),
+            quote! { #path }.to_string()
+        );
+    }
+
+    #[test]
+    fn extract_method_args() {
+        let mut attrs = vec![
+            parse_quote!(#[doc = "Documentation"]),
+            parse_quote!(#[dummies(strict = false)]),
+        ];
+
+        let method_args = MethodArgs::extract(&mut attrs).unwrap();
+
+        assert_eq!(Some(Mode::Default), method_args.mode);
+        assert_eq!(1, attrs.len());
+    }
+
+    #[test]
+    fn strict_and_lenient_are_mutually_exclusive() {
+        let result = given(quote! { strict, lenient });
+        let Err(error) = result else {
+            panic!("Expected error for conflicting arguments");
+        };
+        assert_eq!(
+            "only one of `strict` and `lenient` may be specified",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn body_args_roundtrip() {
+        let args = given(quote! {
+            crate = my::krate, lenient, on_unimplemented = todo!, poll = ready
+        })
+        .unwrap();
+
+        let parsed = given(args.body_args()).unwrap();
+
+        assert_eq!(args.body_args().to_string(), parsed.body_args().to_string());
+        assert_eq!(Mode::Lenient, parsed.mode);
+        assert_eq!(PollArg::Ready, parsed.poll);
+    }
+
+    #[test]
+    fn poll_is_pending_or_ready() {
+        assert_eq!(PollArg::Pending, given(quote! {}).unwrap().poll);
+        assert_eq!(PollArg::Ready, given(quote! { poll = ready }).unwrap().poll);
+
+        let result = given(quote! { poll = later });
+        let Err(error) = result else {
+            panic!("Expected error for unknown variant");
+        };
+        assert_eq!("expected `pending` or `ready`", error.to_string());
+    }
+
+    #[test]
+    fn unknown_argument_is_an_error() {
+        let result = given(quote! { foobar });
+        let Err(error) = result else {
+            panic!("Expected error for unknown argument");
+        };
+        assert_eq!("unsupported dummies argument", error.to_string());
+    }
+
+    fn given(attr: proc_macro2::TokenStream) -> syn::Result<Args> {
+        let mut args = Args::default();
+        syn::meta::parser(|meta| args.parse(meta)).parse2(attr)?;
+        Ok(args)
+    }
+}
@@ -1,27 +1,105 @@
 mod default_body;
 
-use self::default_body::default_body_strategy;
+use self::default_body::{DefaultBodyStrategy, default_body_strategy};
 
+use crate::args::{Args, DummyArg, MethodArgs, Mode, OnUnimplemented, PollArg};
+
+use quote::quote;
 use syn::{
-    FnArg, Ident, ItemTrait, Pat, PatWild, Token, TraitItem, TraitItemFn, punctuated::Punctuated,
-    spanned::Spanned, token::Comma,
+    Block, FnArg, Ident, ItemTrait, Pat, PatWild, Stmt, Token, TraitItem, TraitItemFn, parse_quote,
+    punctuated::Punctuated, spanned::Spanned, token::Comma,
 };
 
+/// A default implementation generated for a method of the trait.
+pub struct GeneratedDefault {
+    fn_name: Ident,
+    strategy: DefaultBodyStrategy,
+    mode: Mode,
+    /// Implementation of the method for the dummy, if it differs from the default of the trait.
+    dummy_override: Option<DummyOverride>,
+}
+
+/// Implementation of a method for the dummy, together with the strategy it has been generated
+/// from.
+struct DummyOverride {
+    strategy: DefaultBodyStrategy,
+    fn_item: TraitItemFn,
+}
+
+impl GeneratedDefault {
+    /// Human readable description of what the implementation of the dummy does, e.g. "returns
+    /// `None`".
+    pub fn describe(&self) -> String {
+        describe_for_dummy(&self.strategy, self.dummy_override.as_ref(), self.mode)
+    }
+
+    /// One line summary of the strategy and mode chosen for the method. Intended for debugging.
+    pub fn debug_line(&self) -> String {
+        format!("{}: {} ({:?})", self.fn_name, self.strategy, self.mode)
+    }
+
+    /// Implementation of the method for the dummy, which knows itself and its associated types to
+    /// implement `Default`. `None` if the dummy uses the default implementation of the trait.
+    pub fn dummy_override(&self) -> Option<&TraitItemFn> {
+        self.dummy_override.as_ref().map(|dummy| &dummy.fn_item)
+    }
+}
+
+/// Describes the override for the dummy if there is one, and the default of the trait otherwise.
+fn describe_for_dummy(
+    strategy: &DefaultBodyStrategy,
+    dummy_override: Option<&DummyOverride>,
+    mode: Mode,
+) -> String {
+    dummy_override
+        .map_or(strategy, |dummy| &dummy.strategy)
+        .describe(mode)
+}
+
 /// Generate a double trait which mirrors the original trait's methods and provides default
-/// implementations using `unimplemented!()`.
-pub fn double_trait(org_trait: ItemTrait) -> syn::Result<ItemTrait> {
+/// implementations using `unimplemented!()`. Also returns the generated default implementations.
+pub fn double_trait(
+    org_trait: ItemTrait,
+    args: &Args,
+) -> syn::Result<(ItemTrait, Vec<GeneratedDefault>)> {
+    let mut defaults = Vec::new();
     let items = org_trait
         .items
         .into_iter()
-        .map(|item| transform_trait_item(item, org_trait.ident.clone()))
+        .map(|item| transform_trait_item(item, org_trait.ident.clone(), args, &mut defaults))
         .collect::<syn::Result<_>>()?;
-    Ok(ItemTrait { items, ..org_trait })
+    let mut attrs = org_trait.attrs;
+    if args.doc_summary && !defaults.is_empty() {
+        attrs.push(parse_quote!(#[doc = ""]));
+        attrs.push(parse_quote!(#[doc = " | Method | Dummy default |"]));
+        attrs.push(parse_quote!(#[doc = " |---|---|"]));
+        for default in &defaults {
+            let row = format!(" | `{}` | {} |", default.fn_name, default.describe());
+            attrs.push(parse_quote!(#[doc = #row]));
+        }
+    }
+    let double_trait = ItemTrait {
+        attrs,
+        items,
+        ..org_trait
+    };
+    Ok((double_trait, defaults))
 }
 
-fn transform_trait_item(trait_item: TraitItem, double_trait_name: Ident) -> syn::Result<TraitItem> {
+fn transform_trait_item(
+    trait_item: TraitItem,
+    double_trait_name: Ident,
+    args: &Args,
+    defaults: &mut Vec<GeneratedDefault>,
+) -> syn::Result<TraitItem> {
     // We are only interessted in transforming functions
     let transformed_trait_item = match trait_item {
-        TraitItem::Fn(fn_item) => TraitItem::Fn(transform_function(fn_item, double_trait_name)?),
+        TraitItem::Fn(fn_item) => TraitItem::Fn(transform_function(
+            fn_item,
+            double_trait_name,
+            args,
+            defaults,
+        )?),
         _ => {
             // If it is not a function, we forward the original Item
             trait_item
@@ -34,29 +112,186 @@ fn transform_trait_item(trait_item: TraitItem, double_trait_name: Ident) -> syn:
 fn transform_function(
     mut fn_item: TraitItemFn,
     double_trait_name: Ident,
+    args: &Args,
+    defaults: &mut Vec<GeneratedDefault>,
 ) -> syn::Result<TraitItemFn> {
+    // Strip our own attributes first, they must not end up in the generated code.
+    let method_args = MethodArgs::extract(&mut fn_item.attrs)?;
+
     if fn_item.default.is_some() {
         return Ok(fn_item);
     }
 
-    // We are stripping parameter names in order to avoid warnings regarding unused variables, since
-    // our default implementation is not making use of any arguments.
-    strip_parameter_names(&mut fn_item.sig.inputs);
+    let mut return_type_info = default_body_strategy(&fn_item.sig.output)?;
+    // Arguments on the method take precedence over the ones passed to the trait.
+    let args = &Args {
+        mode: method_args.mode.unwrap_or(args.mode),
+        ..args.clone()
+    };
+    if args.poll == PollArg::Ready {
+        return_type_info.poll_ready();
+    }
+
+    // The trait does not know `Self` or its associated types, but the dummy does. So the dummy may
+    // return a value rather than panicking. Only the dummies we provide are known to implement
+    // `Default`, and so are the associated types we choose for them.
+    let provided_dummy = matches!(args.dummy, DummyArg::Shared | DummyArg::Struct(_));
+    let dummy_override = match return_type_info.resolve_for_dummy() {
+        Some(strategy) if provided_dummy && args.mode != Mode::Strict => {
+            let fn_item =
+                with_default_body(fn_item.clone(), &strategy, double_trait_name.clone(), args)?;
+            Some(DummyOverride { strategy, fn_item })
+        }
+        _ => None,
+    };
+
+    if args.doc {
+        let description = describe_for_dummy(&return_type_info, dummy_override.as_ref(), args.mode);
+        let line = format!(" Dummy default: {description}.");
+        fn_item.attrs.push(parse_quote!(#[doc = ""]));
+        fn_item.attrs.push(parse_quote!(#[doc = #line]));
+    }
+
+    let fn_item = with_default_body(fn_item, &return_type_info, double_trait_name, args)?;
+    defaults.push(GeneratedDefault {
+        fn_name: fn_item.sig.ident.clone(),
+        strategy: return_type_info,
+        mode: args.mode,
+        dummy_override,
+    });
+
+    Ok(fn_item)
+}
+
+/// Adds a default implementation following `strategy` to the method.
+fn with_default_body(
+    mut fn_item: TraitItemFn,
+    strategy: &DefaultBodyStrategy,
+    double_trait_name: Ident,
+    args: &Args,
+) -> syn::Result<TraitItemFn> {
+    // Unless the default implementation panics right away, strict mode makes it do so. This
+    // includes lazy strategies like `impl Iterator`, which would otherwise only panic once their
+    // items are evaluated. Compile errors need no panic.
+    let check_strict = !strategy.panics_immediately(args.mode) && !strategy.is_compile_error();
+    let strict = args.mode == Mode::Strict;
+
+    // In verbose mode we mention the arguments in the panic message. Of course this only makes
+    // sense if there is a panic message in the first place.
+    let verbose = args.verbose && !strategy.is_compile_error();
+
+    // We are stripping parameter names in order to avoid warnings regarding unused variables,
+    // since our default implementation is not making use of any arguments. In verbose mode we keep
+    // the names of arguments, which are mentioned in the panic message.
+    strip_parameter_names(&mut fn_item.sig.inputs, verbose);
 
-    let return_type_info = default_body_strategy(&fn_item.sig.output);
     let fn_name = fn_item.sig.ident.clone();
 
-    let default_impl = return_type_info.default_body(&fn_item, double_trait_name, fn_name);
+    let mut default_impl =
+        strategy.default_body(&fn_item, double_trait_name.clone(), fn_name.clone(), args)?;
 
-    fn_item.default = Some(default_impl);
+    if check_strict {
+        let krate = &args.krate;
+        let panic =
+            DefaultBodyStrategy::Other.default_body(&fn_item, double_trait_name, fn_name, args)?;
+        // A handler function passed to `on_unimplemented` may return, rather than panic. Strict
+        // mode must not fall through to the safe default in that case.
+        let panic: Block = match args.on_unimplemented {
+            OnUnimplemented::Macro(_) => panic,
+            OnUnimplemented::Function(_) => parse_quote! {{
+                let () = #panic;
+                #[allow(unreachable_code)]
+                {
+                    unreachable!("`on_unimplemented` must not return in strict mode")
+                }
+            }},
+        };
+        default_impl = if strict {
+            // We still keep the safe default around, so the compiler is able to infer types like
+            // `impl Iterator`.
+            parse_quote! {{
+                #panic
+                #[allow(unreachable_code)]
+                #default_impl
+            }}
+        } else {
+            // Strict mode can also be enabled at runtime for individual tests.
+            let mut stmts = vec![parse_quote! {
+                if #krate::__private::is_strict_mode() #panic
+            }];
+            stmts.append(&mut default_impl.stmts);
+            Block {
+                stmts,
+                ..default_impl
+            }
+        };
+    }
 
+    if verbose {
+        let mut stmts = verbose_prelude(&fn_item, args);
+        stmts.append(&mut default_impl.stmts);
+        default_impl.stmts = stmts;
+        // Async functions do not support `#[track_caller]`
+        if fn_item.sig.asyncness.is_none() {
+            fn_item.attrs.push(parse_quote!(#[track_caller]));
+        }
+    }
+
+    fn_item.default = Some(default_impl);
     Ok(fn_item)
 }
 
-fn strip_parameter_names(input: &mut Punctuated<FnArg, Comma>) {
+/// Statements declaring the variables referenced by panic messages in verbose mode. We format the
+/// arguments eagerly into a `String`, so the body does not need to capture them. This way the
+/// arguments do not interfere with e.g. `Send` bounds of returned futures or iterators.
+fn verbose_prelude(fn_item: &TraitItemFn, args: &Args) -> Vec<Stmt> {
+    let krate = &args.krate;
+    let formatted_args = fn_item.sig.inputs.iter().filter_map(|arg| {
+        let FnArg::Typed(pat_type) = arg else {
+            return None;
+        };
+        let formatted = if let Pat::Ident(pat_ident) = &*pat_type.pat {
+            let ident = &pat_ident.ident;
+            // Autoref specialization, uses the `Debug` implementation of the argument if it exists
+            // and falls back to `_` otherwise.
+            quote! {
+                format!(
+                    "{}: {}",
+                    stringify!(#ident),
+                    (&#krate::__private::DebugArg(&#ident)).debug_arg()
+                )
+            }
+        } else {
+            quote! { String::from("_") }
+        };
+        Some(formatted)
+    });
+    let caller = fn_item.sig.asyncness.is_none().then(|| {
+        quote! {
+            let double_trait_caller = std::panic::Location::caller();
+        }
+    });
+    let prelude: Block = parse_quote! {{
+        use #krate::__private::{ViaDebug as _, ViaFallback as _};
+        let double_trait_args = <[String]>::join(&[#(#formatted_args),*], ", ");
+        #caller
+    }};
+    prelude.stmts
+}
+
+/// Replaces the patterns of all parameters with `_`. If `keep_names` is set, parameters bound to a
+/// plain name keep it, only without `mut`. Other patterns are not mentioned in panic messages, so
+/// their bindings would be unused.
+fn strip_parameter_names(input: &mut Punctuated<FnArg, Comma>, keep_names: bool) {
     for arg in input {
         // We are only interested in pattern type. No need to transform `self`
         if let FnArg::Typed(pat_type) = arg {
+            if let Pat::Ident(pat_ident) = &mut *pat_type.pat
+                && keep_names
+            {
+                pat_ident.mutability = None;
+                continue;
+            }
             *pat_type.pat = Pat::Wild(PatWild {
                 attrs: Vec::new(),
                 underscore_token: Token![_](pat_type.span()),
@@ -68,8 +303,9 @@ fn strip_parameter_names(input: &mut Punctuated<FnArg, Comma>) {
 #[cfg(test)]
 mod tests {
     use super::double_trait;
+    use crate::args::{Args, DummyArg, Mode, OnUnimplemented};
     use quote::quote;
-    use syn::{ItemTrait, parse2};
+    use syn::{ItemTrait, TraitItem, parse_quote, parse2};
 
     #[test]
     fn default_impl_for_method_returning_result_unit() {
@@ -81,13 +317,19 @@ mod tests {
         });
 
         // When
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then
         let actual = quote! { #double_trait };
         let expected = quote! {
             trait MyTrait {
                 fn method(&self) -> Result<(), MyError> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     let inner = {};
                     # [allow (unreachable_code)]
                     Ok(inner)
@@ -107,7 +349,7 @@ mod tests {
         });
 
         // When
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then
         let actual = quote! { #double_trait };
@@ -117,6 +359,7 @@ mod tests {
                     let inner = {
                         let double_trait_name = stringify!(MyTrait);
                         let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                         unimplemented ! ("{double_trait_name}::{fn_name}")
                     };
                     # [allow (unreachable_code)]
@@ -137,7 +380,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation for the method which uses
         // an async block
@@ -145,6 +388,12 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method(&self) -> impl Future<Output = ()> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     async { }
                 }
             }
@@ -162,7 +411,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation for the method which uses
         // an async block
@@ -170,9 +419,16 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method(&self) -> impl Future<Output = i32> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     async {
                         let double_trait_name = stringify!(MyTrait);
                         let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                         unimplemented!("{double_trait_name}::{fn_name}")
                     }
                 }
@@ -191,7 +447,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation for the method which uses
         // an empty array iterator
@@ -199,12 +455,19 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method(&self) -> impl Iterator<Item = String> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     #[allow(unreachable_code)]
                     std::iter::from_fn(move | | {
                         if false {
                             Some({
                                 let double_trait_name = stringify!(MyTrait);
                                 let fn_name = stringify!(method);
+                                double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                                 unimplemented!("{double_trait_name}::{fn_name}")
                             })
                         } else {
@@ -227,13 +490,20 @@ mod tests {
         });
 
         // When
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then
         let actual = quote! { #double_trait };
         let expected = quote! {
             trait MyTrait {
-                fn method(_: i32) {}
+                fn method(_: i32) {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                }
             }
         };
         assert_eq!(actual.to_string(), expected.to_string());
@@ -249,13 +519,19 @@ mod tests {
         });
 
         // When
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then
         let actual = quote! { #double_trait };
         let expected = quote! {
             trait MyTrait {
                 fn method() -> Option<i32> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     None
                 }
             }
@@ -273,13 +549,19 @@ mod tests {
         });
 
         // When
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then
         let actual = quote! { #double_trait };
         let expected = quote! {
             trait MyTrait {
                 fn method() -> Vec<i32> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     Vec::new()
                 }
             }
@@ -297,7 +579,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation with unimplemented!() which
         // uses the trait and function name in the error message.
@@ -307,6 +589,7 @@ mod tests {
                 fn method(_: i32) -> i32 {
                     let double_trait_name = stringify!(MyTrait);
                     let fn_name = stringify!(method);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                     unimplemented!("{double_trait_name}::{fn_name}")
                 }
             }
@@ -324,7 +607,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation which generates a nice compile
         // error.
@@ -332,10 +615,9 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method() -> impl UnsupportedTrait {
-                    compile_error!(
-                        "impl Trait is currently not supported by double-trait. Apart from the \
-                        special cases of `impl Future` and `impl Stream`."
-                    )
+                    ::core::compile_error! {
+                        "impl Trait is currently not supported by double-trait. Apart from the special cases of `impl Future` and `impl Stream`."
+                    }
                 }
             }
         };
@@ -353,7 +635,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation which generates a nice compile
         // error.
@@ -361,7 +643,27 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method() -> impl Stream {
-                    futures_util::stream::empty()
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    #[allow(unreachable_code)]
+                    double_trait::__private::futures_util::stream::iter(
+                        std::iter::from_fn(move | | {
+                            if false {
+                                Some({
+                                    let double_trait_name = stringify!(MyTrait);
+                                    let fn_name = stringify!(method);
+                                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                                    unimplemented!("{double_trait_name}::{fn_name}")
+                                })
+                            } else {
+                                None
+                            }
+                        })
+                    )
                 }
             }
         };
@@ -379,7 +681,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation which generates a nice compile
         // error.
@@ -387,10 +689,9 @@ mod tests {
         let expected = quote! {
             trait MyTrait {
                 fn method() -> impl Stream {
-                    compile_error!(
-                        "impl Stream is only supported if the `stream` feature of \
-                                double-trait is activated."
-                    )
+                    ::core::compile_error! {
+                        "impl Stream is only supported if the `stream` feature of double-trait is activated."
+                    }
                 }
             }
         };
@@ -407,7 +708,7 @@ mod tests {
         });
 
         // When generating the double trait
-        let double_trait = double_trait(org_trait).unwrap();
+        let (double_trait, _defaults) = double_trait(org_trait, &Args::default()).unwrap();
 
         // Then the double trait should have a default implementation for the method which uses
         // an async block
@@ -417,6 +718,7 @@ mod tests {
                 fn method(_: i32) -> i32{
                     let double_trait_name = stringify!(MyTrait);
                     let fn_name = stringify!(method);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                     unimplemented!("{double_trait_name}::{fn_name}")
                 }
             }
@@ -424,6 +726,381 @@ mod tests {
         assert_eq!(actual.to_string(), expected.to_string());
     }
 
+    #[test]
+    fn verbose_default_impl_mentions_arguments_and_caller() {
+        // Given an original trait with a method returning an i32
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self, x: i32) -> i32;
+            }
+        });
+
+        // When generating the double trait in verbose mode
+        let args = Args {
+            verbose: true,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the parameter names are kept and mentioned in the panic message, together with the
+        // location of the caller.
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                #[track_caller]
+                fn method(&self, x: i32) -> i32 {
+                    use double_trait::__private::{ViaDebug as _, ViaFallback as _};
+                    let double_trait_args = <[String]>::join(
+                        &[format!(
+                            "{}: {}",
+                            stringify!(x),
+                            (&double_trait::__private::DebugArg(&x)).debug_arg()
+                        )],
+                        ", "
+                    );
+                    let double_trait_caller = std::panic::Location::caller();
+                    let double_trait_name = stringify!(MyTrait);
+                    let fn_name = stringify!(method);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                    unimplemented!(
+                        "{double_trait_name}::{fn_name}({double_trait_args}) called at \
+                        {double_trait_caller}"
+                    )
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn verbose_default_impl_ignores_patterns() {
+        // Given an original trait with a method destructuring its argument and a mutable one
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self, (a, b): (i32, i32), mut c: i32);
+            }
+        });
+
+        // When generating the double trait in verbose mode
+        let args = Args {
+            verbose: true,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the pattern is replaced by `_` and mentioned as such, so there are no unused
+        // bindings. Named arguments lose their `mut`.
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                #[track_caller]
+                fn method(&self, _: (i32, i32), c: i32) {
+                    use double_trait::__private::{ViaDebug as _, ViaFallback as _};
+                    let double_trait_args = <[String]>::join(
+                        &[
+                            String::from("_"),
+                            format!(
+                                "{}: {}",
+                                stringify!(c),
+                                (&double_trait::__private::DebugArg(&c)).debug_arg()
+                            )
+                        ],
+                        ", "
+                    );
+                    let double_trait_caller = std::panic::Location::caller();
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!(
+                            "{double_trait_name}::{fn_name}({double_trait_args}) called at \
+                        {double_trait_caller}"
+                        )
+                    }
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn custom_on_unimplemented_macro_in_nested_strategy() {
+        // Given an original trait with a method returning a future of a result
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self) -> impl Future<Output = Result<i32, MyError>>;
+            }
+        });
+
+        // When generating the double trait with `on_unimplemented = todo!`
+        let args = Args {
+            on_unimplemented: OnUnimplemented::Macro(parse_quote!(todo)),
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the nested default uses `todo!` instead of `unimplemented!`
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                fn method(&self) -> impl Future<Output = Result<i32, MyError> > {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        todo!("{double_trait_name}::{fn_name}")
+                    }
+                    async {
+                        let inner = {
+                            let double_trait_name = stringify!(MyTrait);
+                            let fn_name = stringify!(method);
+                            double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                            todo!("{double_trait_name}::{fn_name}")
+                        };
+                        #[allow(unreachable_code)]
+                        Ok(inner)
+                    }
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn custom_on_unimplemented_function() {
+        // Given an original trait with a method returning an i32
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self) -> i32;
+            }
+        });
+
+        // When generating the double trait with `on_unimplemented = my_handler`
+        let args = Args {
+            on_unimplemented: OnUnimplemented::Function(parse_quote!(my_handler)),
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the handler is invoked with the formatted message
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                fn method(&self) -> i32 {
+                    let double_trait_name = stringify!(MyTrait);
+                    let fn_name = stringify!(method);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                    my_handler(&format!("{double_trait_name}::{fn_name}"))
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn strict_default_impl_panics_even_for_option() {
+        // Given an original trait with a method returning an Option
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self) -> Option<i32>;
+            }
+        });
+
+        // When generating the double trait in strict mode
+        let args = Args {
+            mode: Mode::Strict,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the default implementation panics before returning `None`
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                fn method(&self) -> Option<i32> {
+                    {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    #[allow(unreachable_code)]
+                    {
+                        None
+                    }
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn lenient_default_impl_falls_back_to_default() {
+        // Given an original trait with a method returning a type without special treatment
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn method(&self) -> i32;
+            }
+        });
+
+        // When generating the double trait in lenient mode
+        let args = Args {
+            mode: Mode::Lenient,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the default implementation uses `Default` if available and panics otherwise
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                fn method(&self) -> i32 {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    use double_trait::__private::{ViaDefault as _, ViaUnimplemented as _};
+                    (&double_trait::__private::LenientDefault::<i32>::new()).lenient_default(| | {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(method);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    })
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn document_generated_defaults() {
+        // Given an original trait with a method returning an Option and one returning an i32
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn find(&self) -> Option<i32>;
+                fn answer(&self) -> i32;
+            }
+        });
+
+        // When generating the double trait with documentation of the defaults
+        let args = Args {
+            doc: true,
+            doc_summary: true,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then each method and the trait itself document the generated default
+        let ItemTrait { attrs, items, .. } = double_trait;
+        let expected_attrs = quote! {
+            #[doc = ""]
+            #[doc = " | Method | Dummy default |"]
+            #[doc = " |---|---|"]
+            #[doc = " | `find` | returns `None` |"]
+            #[doc = " | `answer` | panics |"]
+        };
+        assert_eq!(
+            quote! { #(#attrs)* }.to_string(),
+            expected_attrs.to_string()
+        );
+        let TraitItem::Fn(find) = &items[0] else {
+            panic!("Expected method");
+        };
+        let find_attrs = &find.attrs;
+        let expected_find_attrs = quote! {
+            #[doc = ""]
+            #[doc = " Dummy default: returns `None`."]
+        };
+        assert_eq!(
+            quote! { #(#find_attrs)* }.to_string(),
+            expected_find_attrs.to_string()
+        );
+    }
+
+    #[test]
+    fn document_defaults_of_the_dummy() {
+        // Given a method returning `Self`, which the dummy can construct, but the trait can not
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn duplicate(&self) -> Self where Self: Sized;
+            }
+        });
+
+        // When documenting the defaults for the shared dummy and for a custom one
+        let shared = Args {
+            doc: true,
+            doc_summary: true,
+            ..Args::default()
+        };
+        let custom = Args {
+            dummy: DummyArg::Custom(Box::new(parse_quote!(MyDummy))),
+            ..shared.clone()
+        };
+        let (shared, _defaults) = double_trait(org_trait.clone(), &shared).unwrap();
+        let (custom, _defaults) = double_trait(org_trait, &custom).unwrap();
+
+        // Then the documentation describes the implementation of the dummy, which only the shared
+        // dummy overrides
+        let docs = |double_trait: ItemTrait| {
+            let TraitItem::Fn(duplicate) = &double_trait.items[0] else {
+                panic!("Expected method");
+            };
+            let attrs = double_trait.attrs.iter().chain(&duplicate.attrs);
+            quote! { #(#attrs)* }.to_string()
+        };
+        let expected_shared = quote! {
+            #[doc = ""]
+            #[doc = " | Method | Dummy default |"]
+            #[doc = " |---|---|"]
+            #[doc = " | `duplicate` | returns `Default::default()` |"]
+            #[doc = ""]
+            #[doc = " Dummy default: returns `Default::default()`."]
+        };
+        assert_eq!(expected_shared.to_string(), docs(shared));
+        let expected_custom = quote! {
+            #[doc = ""]
+            #[doc = " | Method | Dummy default |"]
+            #[doc = " |---|---|"]
+            #[doc = " | `duplicate` | panics |"]
+            #[doc = ""]
+            #[doc = " Dummy default: panics."]
+        };
+        assert_eq!(expected_custom.to_string(), docs(custom));
+    }
+
+    #[test]
+    fn dummies_attributes_on_methods_are_removed() {
+        // Given an original trait with a method annotated with `dummies`
+        let org_trait = given(quote! {
+            trait MyTrait {
+                #[dummies(strict = false)]
+                fn method(&self) -> Option<i32> {
+                    Some(42)
+                }
+            }
+        });
+
+        // When generating the double trait in strict mode
+        let args = Args {
+            mode: Mode::Strict,
+            ..Args::default()
+        };
+        let (double_trait, _defaults) = double_trait(org_trait, &args).unwrap();
+
+        // Then the method attribute is removed
+        let actual = quote! { #double_trait };
+        let expected = quote! {
+            trait MyTrait {
+                fn method(&self) -> Option<i32> {
+                    Some(42)
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
     fn given(item: proc_macro2::TokenStream) -> ItemTrait {
         parse2(item).unwrap()
     }
@@ -1,12 +1,16 @@
-use quote::{quote, quote_spanned};
+use std::fmt;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
 use syn::{
     AngleBracketedGenericArguments, Block, GenericArgument, Ident, PathArguments, ReturnType,
-    TraitItemFn, Type, TypeParamBound, parse2, spanned::Spanned,
+    TraitItemFn, Type, TypeParamBound, TypePath, TypeReference, parse2,
 };
 
+use crate::args::{Args, Mode, OnUnimplemented};
+
 /// Since we ignore all arguments in the body the return type alone decides what the body of the
 /// default implementation is.
-#[derive(Debug, PartialEq, Eq)]
 pub enum DefaultBodyStrategy {
     /// If the function does not return, we want the default implementation to be empty, rather than
     /// using `unimplemented!()`.
@@ -23,46 +27,318 @@ pub enum DefaultBodyStrategy {
     },
     ImplStream {
         /// The associated Item type of the Stream
-        _item: Option<Box<DefaultBodyStrategy>>,
+        item: Option<Box<DefaultBodyStrategy>>,
     },
     Result {
         // The `Ok` type of the Result
         ok: Box<DefaultBodyStrategy>,
     },
-    Option,
+    Option {
+        /// The `Some` type of the Option
+        some: Box<DefaultBodyStrategy>,
+    },
     Vec,
+    /// `Poll<T>`, we return `Pending`, unless configured to be `Ready`.
+    Poll {
+        /// The strategy for `T`
+        inner: Box<DefaultBodyStrategy>,
+        /// Return `Ready` of the default of `T`, rather than `Pending`. Set via `poll = ready`.
+        ready: bool,
+    },
+    /// `ControlFlow<B, C>`, we continue with the default of `C`.
+    ControlFlow {
+        /// The strategy for `C`
+        inner: Box<DefaultBodyStrategy>,
+    },
+    /// `std::cmp::Ordering`, we return `Equal`.
+    Ordering,
+    /// `Box<T>`, `Rc<T>` or `Arc<T>`. We wrap the default of `T`.
+    SmartPointer {
+        pointer: SmartPointer,
+        /// The strategy for `T`
+        inner: Box<DefaultBodyStrategy>,
+    },
+    /// `Cow<'_, B>`. We borrow an empty `str` or slice, or own the default of `B`.
+    Cow {
+        /// The strategy for `B`
+        inner: Box<DefaultBodyStrategy>,
+    },
+    /// `&str`, we return an empty string.
+    Str,
+    /// `&[T]` or `&mut [T]`, we return an empty slice.
+    Slice,
+    /// A reference to any other type, e.g. `&Config`. We can not borrow from anything, so the trait
+    /// panics.
+    Reference(Box<TypeReference>),
+    /// A reference to a leaked `Default::default()` of the referenced type. Used by the dummy, in
+    /// order to not panic for references. Falls back to panicking, if the type does not implement
+    /// `Default`.
+    Leak(Box<TypeReference>),
     UnknownImpl,
+    /// A type we do not know a sensible value for. In lenient mode we still try `Default`.
+    UnknownType(Box<Type>),
+    /// An associated type of `Self`, e.g. `Self::Output`. Unknown to the trait, but known to the
+    /// implementation for the dummy.
+    AssociatedType(Box<Type>),
+    /// `Self`. Unknown to the trait, but known to the implementation for the dummy.
+    SelfType,
+    /// A type known to implement `Default`, e.g. an associated type of the dummy.
+    Default,
+    /// We do not know anything about the type, e.g. because it is missing.
     Other,
 }
 
 impl DefaultBodyStrategy {
+    /// `true` if the default body is a compile error, rather than returning or panicking.
+    pub fn is_compile_error(&self) -> bool {
+        match self {
+            DefaultBodyStrategy::UnknownImpl => true,
+            DefaultBodyStrategy::ImplStream { .. } => !cfg!(feature = "stream"),
+            _ => false,
+        }
+    }
+
+    /// `true` if the default body panics right away, without returning a value first. E.g. an
+    /// `impl Future` whose output panics is not included, since it panics only once awaited.
+    pub fn panics_immediately(&self, mode: Mode) -> bool {
+        match self {
+            DefaultBodyStrategy::Other | DefaultBodyStrategy::SelfType => true,
+            DefaultBodyStrategy::UnknownType(ty) | DefaultBodyStrategy::AssociatedType(ty) => {
+                mode != Mode::Lenient || mentions_impl_trait(quote! { #ty })
+            }
+            DefaultBodyStrategy::Reference(_) => mode != Mode::Lenient,
+            DefaultBodyStrategy::Result { ok: inner }
+            | DefaultBodyStrategy::ControlFlow { inner }
+            | DefaultBodyStrategy::Poll { inner, ready: true } => inner.panics_immediately(mode),
+            DefaultBodyStrategy::SmartPointer { inner, .. }
+            | DefaultBodyStrategy::Cow { inner } => {
+                inner.may_be_unsized() || inner.panics_immediately(mode)
+            }
+            _ => false,
+        }
+    }
+
+    /// `true` if the type mentions `impl Trait`. The compiler can only infer such types from a
+    /// value, so we can not just return e.g. `None`.
+    fn mentions_impl_trait(&self) -> bool {
+        match self {
+            DefaultBodyStrategy::ImplFuture { .. }
+            | DefaultBodyStrategy::ImplIterator { .. }
+            | DefaultBodyStrategy::ImplStream { .. }
+            | DefaultBodyStrategy::UnknownImpl => true,
+            DefaultBodyStrategy::Result { ok: inner }
+            | DefaultBodyStrategy::Option { some: inner }
+            | DefaultBodyStrategy::Poll { inner, .. }
+            | DefaultBodyStrategy::ControlFlow { inner }
+            | DefaultBodyStrategy::SmartPointer { inner, .. }
+            | DefaultBodyStrategy::Cow { inner } => inner.mentions_impl_trait(),
+            DefaultBodyStrategy::UnknownType(ty) => mentions_impl_trait(quote! { #ty }),
+            DefaultBodyStrategy::Empty
+            | DefaultBodyStrategy::Vec
+            | DefaultBodyStrategy::Ordering
+            | DefaultBodyStrategy::Str
+            | DefaultBodyStrategy::Slice
+            | DefaultBodyStrategy::Reference(_)
+            | DefaultBodyStrategy::Leak(_)
+            | DefaultBodyStrategy::AssociatedType(_)
+            | DefaultBodyStrategy::SelfType
+            | DefaultBodyStrategy::Default
+            | DefaultBodyStrategy::Other => false,
+        }
+    }
+
+    /// Makes `Poll` return `Ready` with the default of its inner type, rather than `Pending`.
+    pub fn poll_ready(&mut self) {
+        match self {
+            DefaultBodyStrategy::Poll { inner, ready } => {
+                *ready = true;
+                inner.poll_ready();
+            }
+            DefaultBodyStrategy::ImplFuture { output: inner }
+            | DefaultBodyStrategy::ImplIterator { item: inner }
+            | DefaultBodyStrategy::ImplStream { item: inner } => {
+                if let Some(inner) = inner {
+                    inner.poll_ready();
+                }
+            }
+            DefaultBodyStrategy::Result { ok: inner }
+            | DefaultBodyStrategy::Option { some: inner }
+            | DefaultBodyStrategy::ControlFlow { inner }
+            | DefaultBodyStrategy::SmartPointer { inner, .. }
+            | DefaultBodyStrategy::Cow { inner } => inner.poll_ready(),
+            _ => (),
+        }
+    }
+
+    /// `true` for types which are unknown to the trait and may be unsized, i.e. `Self` and its
+    /// associated types.
+    fn may_be_unsized(&self) -> bool {
+        matches!(
+            self,
+            DefaultBodyStrategy::SelfType | DefaultBodyStrategy::AssociatedType(_)
+        )
+    }
+
+    /// Strategy for the implementation for the dummy, which knows `Self` and its associated types
+    /// to implement `Default`. The dummy also leaks values in order to return references. `None`
+    /// if the dummy can not do better than the trait.
+    pub fn resolve_for_dummy(&self) -> Option<DefaultBodyStrategy> {
+        let resolve = |inner: &Option<Box<DefaultBodyStrategy>>| {
+            let resolved = inner.as_deref()?.resolve_for_dummy()?;
+            Some(Some(Box::new(resolved)))
+        };
+        let resolved = match self {
+            DefaultBodyStrategy::AssociatedType(_) | DefaultBodyStrategy::SelfType => {
+                DefaultBodyStrategy::Default
+            }
+            DefaultBodyStrategy::Reference(reference) => {
+                DefaultBodyStrategy::Leak(reference.clone())
+            }
+            DefaultBodyStrategy::ImplFuture { output } => DefaultBodyStrategy::ImplFuture {
+                output: resolve(output)?,
+            },
+            DefaultBodyStrategy::ImplIterator { item } => DefaultBodyStrategy::ImplIterator {
+                item: resolve(item)?,
+            },
+            DefaultBodyStrategy::ImplStream { item } => DefaultBodyStrategy::ImplStream {
+                item: resolve(item)?,
+            },
+            DefaultBodyStrategy::Result { ok } => DefaultBodyStrategy::Result {
+                ok: Box::new(ok.resolve_for_dummy()?),
+            },
+            DefaultBodyStrategy::SmartPointer { pointer, inner } => {
+                DefaultBodyStrategy::SmartPointer {
+                    pointer: *pointer,
+                    inner: Box::new(inner.resolve_for_dummy()?),
+                }
+            }
+            DefaultBodyStrategy::Cow { inner } => DefaultBodyStrategy::Cow {
+                inner: Box::new(inner.resolve_for_dummy()?),
+            },
+            DefaultBodyStrategy::Poll { inner, ready: true } => DefaultBodyStrategy::Poll {
+                inner: Box::new(inner.resolve_for_dummy()?),
+                ready: true,
+            },
+            DefaultBodyStrategy::ControlFlow { inner } => DefaultBodyStrategy::ControlFlow {
+                inner: Box::new(inner.resolve_for_dummy()?),
+            },
+            _ => return None,
+        };
+        Some(resolved)
+    }
+
+    /// Human readable description of what the default implementation does, e.g. "returns
+    /// `None`". Used to document generated defaults.
+    pub fn describe(&self, mode: Mode) -> String {
+        if matches!(self, DefaultBodyStrategy::UnknownImpl) {
+            return "does not compile".to_owned();
+        }
+        if mode == Mode::Strict {
+            return "panics".to_owned();
+        }
+        match (self, self.describe_value(mode)) {
+            (DefaultBodyStrategy::Empty, _) => "does nothing".to_owned(),
+            (_, Some(value)) => format!("returns {value}"),
+            (_, None) => "panics".to_owned(),
+        }
+    }
+
+    /// Describes the returned value. `None` if the default implementation panics instead.
+    fn describe_value(&self, mode: Mode) -> Option<String> {
+        let value = match self {
+            DefaultBodyStrategy::Empty => "`()`".to_owned(),
+            DefaultBodyStrategy::ImplFuture { output } => {
+                let output = output.as_deref()?.describe_value(mode)?;
+                format!("a future resolving to {output}")
+            }
+            DefaultBodyStrategy::ImplIterator { .. } => "an empty iterator".to_owned(),
+            DefaultBodyStrategy::ImplStream { .. } => "an empty stream".to_owned(),
+            DefaultBodyStrategy::Result { ok } => match **ok {
+                DefaultBodyStrategy::Empty => "`Ok(())`".to_owned(),
+                _ => format!("`Ok` of {}", ok.describe_value(mode)?),
+            },
+            DefaultBodyStrategy::Option { .. } => "`None`".to_owned(),
+            DefaultBodyStrategy::Vec => "an empty `Vec`".to_owned(),
+            DefaultBodyStrategy::Poll { inner, ready } => {
+                if *ready {
+                    format!("`Poll::Ready` of {}", inner.describe_value(mode)?)
+                } else {
+                    "`Poll::Pending`".to_owned()
+                }
+            }
+            DefaultBodyStrategy::ControlFlow { inner } => match **inner {
+                DefaultBodyStrategy::Empty => "`ControlFlow::Continue(())`".to_owned(),
+                _ => format!("`ControlFlow::Continue` of {}", inner.describe_value(mode)?),
+            },
+            DefaultBodyStrategy::Ordering => "`Ordering::Equal`".to_owned(),
+            DefaultBodyStrategy::SmartPointer { pointer, inner } => {
+                let inner = inner.describe_value(mode)?;
+                match pointer {
+                    SmartPointer::Box => format!("a boxed {inner}"),
+                    SmartPointer::Rc | SmartPointer::Arc => format!("an `{pointer}` of {inner}"),
+                }
+            }
+            DefaultBodyStrategy::Cow { inner } => {
+                format!("a `Cow` of {}", inner.describe_value(mode)?)
+            }
+            DefaultBodyStrategy::Default => "`Default::default()`".to_owned(),
+            DefaultBodyStrategy::Str => "`\"\"`".to_owned(),
+            DefaultBodyStrategy::Slice => "an empty slice".to_owned(),
+            DefaultBodyStrategy::Leak(_) => {
+                "a reference to a leaked `Default::default()` if implemented".to_owned()
+            }
+            DefaultBodyStrategy::Reference(_) if mode == Mode::Lenient => {
+                "`Default::default()` if implemented".to_owned()
+            }
+            DefaultBodyStrategy::UnknownType(ty) | DefaultBodyStrategy::AssociatedType(ty)
+                if mode == Mode::Lenient && !mentions_impl_trait(quote! { #ty }) =>
+            {
+                "`Default::default()` if implemented".to_owned()
+            }
+            DefaultBodyStrategy::UnknownImpl
+            | DefaultBodyStrategy::UnknownType(_)
+            | DefaultBodyStrategy::AssociatedType(_)
+            | DefaultBodyStrategy::SelfType
+            | DefaultBodyStrategy::Reference(_)
+            | DefaultBodyStrategy::Other => return None,
+        };
+        Some(value)
+    }
+
     pub fn default_body(
         &self,
         fn_item: &TraitItemFn,
         double_trait_name: Ident,
         fn_name: Ident,
-    ) -> Block {
-        match self {
+        args: &Args,
+    ) -> syn::Result<Block> {
+        let tokens = match self {
             DefaultBodyStrategy::ImplFuture { output } => {
                 // Treat missing Output type like other, i.e. use unimplemented!() in the async
                 // block
                 let output = output.as_deref().unwrap_or(&DefaultBodyStrategy::Other);
-                let inner = output.default_body(fn_item, double_trait_name, fn_name);
+                let inner = output.default_body(fn_item, double_trait_name, fn_name, args)?;
                 // If the method returns an impl Future, we provide a default implementation using
                 // an async block, so that the compiler won't complain about not being able to infer
                 // the type of `impl Future`.
-                parse2(quote! {{ async #inner }}).unwrap()
+                if args.verbose {
+                    // The panic message refers to variables of the verbose prelude. Move them into
+                    // the future, so it does not borrow from the stack frame of the method.
+                    quote! {{ async move #inner }}
+                } else {
+                    quote! {{ async #inner }}
+                }
             }
             DefaultBodyStrategy::ImplIterator { item } => {
                 // If the method returns an impl Iterator, we provide a default implementation using
                 // an iterator returning no elements.
 
                 let item = item.as_deref().unwrap_or(&DefaultBodyStrategy::Other);
-                let inner = item.default_body(fn_item, double_trait_name, fn_name);
+                let inner = item.default_body(fn_item, double_trait_name, fn_name, args)?;
 
                 // We are constructing an empty interator, but we still want to be able to infer an
                 // element type from `#inner` if possible.
-                parse2(quote! {{
+                quote! {{
                     #[allow(unreachable_code)]
                     std::iter::from_fn(move || {
                         if false {
@@ -71,78 +347,297 @@ impl DefaultBodyStrategy {
                             None
                         }
                     })
-                }})
-                .unwrap()
+                }}
             }
-            DefaultBodyStrategy::ImplStream { _item: _ } => {
+            DefaultBodyStrategy::ImplStream { item } => {
                 if cfg!(feature = "stream") {
-                    parse2(quote! {{
-                        futures_util::stream::empty()
-                    }})
-                    .unwrap()
+                    let item = item.as_deref().unwrap_or(&DefaultBodyStrategy::Other);
+                    let inner = item.default_body(fn_item, double_trait_name, fn_name, args)?;
+                    let krate = &args.krate;
+
+                    // Like for `ImplIterator` we construct an empty stream, which still allows the
+                    // compiler to infer the item type from `#inner`. In contrast to
+                    // `futures_util::stream::empty()` this also works for items which are
+                    // `impl Trait` themselves.
+                    //
+                    // We refer to `futures_util` via the reexport of `double_trait`, so users do not
+                    // need to depend on it themselves.
+                    quote! {{
+                        #[allow(unreachable_code)]
+                        #krate::__private::futures_util::stream::iter(
+                            std::iter::from_fn(move || {
+                                if false {
+                                    Some(#inner)
+                                } else {
+                                    None
+                                }
+                            })
+                        )
+                    }}
                 } else {
-                    parse2(quote_spanned! {
-                        fn_item.sig.output.span() => {
-                            compile_error!(
-                                "impl Stream is only supported if the `stream` feature of \
-                                double-trait is activated."
-                            )
-                        }
-                    })
-                    .unwrap()
+                    compile_error_body(
+                        fn_item,
+                        "impl Stream is only supported if the `stream` feature of double-trait is \
+                        activated.",
+                    )
+                }
+            }
+            DefaultBodyStrategy::UnknownType(ty) | DefaultBodyStrategy::AssociatedType(ty)
+                if args.mode == Mode::Lenient && !mentions_impl_trait(quote! { #ty }) =>
+            {
+                lenient_default(ty, fn_item, double_trait_name, fn_name, args)?
+            }
+            DefaultBodyStrategy::Reference(reference) if args.mode == Mode::Lenient => {
+                let ty = Type::Reference((**reference).clone());
+                lenient_default(&ty, fn_item, double_trait_name, fn_name, args)?
+            }
+            DefaultBodyStrategy::Leak(reference) => {
+                let ty = &reference.elem;
+                let value = lenient_default(ty, fn_item, double_trait_name, fn_name, args)?;
+                let leaked = quote! { Box::leak(Box::new(#value)) };
+                if reference.mutability.is_some() {
+                    quote! {{ #leaked }}
+                } else {
+                    quote! {{ &*#leaked }}
                 }
             }
-            DefaultBodyStrategy::Other => {
+            DefaultBodyStrategy::UnknownType(_)
+            | DefaultBodyStrategy::AssociatedType(_)
+            | DefaultBodyStrategy::SelfType
+            | DefaultBodyStrategy::Reference(_)
+            | DefaultBodyStrategy::Other => {
                 // Otherwise, we provide a default implementation using unimplemented!
                 // We can unwrap here, this body should always compile
-                parse2(quote! {{
+                let message = if !args.verbose {
+                    quote! { "{double_trait_name}::{fn_name}" }
+                } else if fn_item.sig.asyncness.is_some() {
+                    // `#[track_caller]` is not supported for async functions, so we can not
+                    // report the caller location.
+                    quote! { "{double_trait_name}::{fn_name}({double_trait_args})" }
+                } else {
+                    quote! {
+                        "{double_trait_name}::{fn_name}({double_trait_args}) called at \
+                        {double_trait_caller}"
+                    }
+                };
+                let on_unimplemented = match &args.on_unimplemented {
+                    OnUnimplemented::Macro(path) => quote! { #path!(#message) },
+                    OnUnimplemented::Function(path) => quote! { #path(&format!(#message)) },
+                };
+                // We report the call before panicking, so it can be detected even if the panic is
+                // swallowed, e.g. in a spawned thread.
+                let krate = &args.krate;
+                quote! {{
                     let double_trait_name = stringify!(#double_trait_name);
                     let fn_name = stringify!(#fn_name);
-                    unimplemented!("{double_trait_name}::{fn_name}")
-                }})
-                .unwrap()
+                    #krate::__private::report_unimplemented(double_trait_name, fn_name);
+                    #on_unimplemented
+                }}
+            }
+            DefaultBodyStrategy::Empty => quote! { { } },
+            DefaultBodyStrategy::Vec => quote! { { Vec::new() } },
+            DefaultBodyStrategy::Ordering => quote! { { std::cmp::Ordering::Equal } },
+            DefaultBodyStrategy::Poll {
+                inner,
+                ready: false,
+            } if inner.mentions_impl_trait() => {
+                // Like for `Option`, `Pending` alone does not tell the compiler which type hides
+                // behind `impl Trait`.
+                let inner = inner.default_body(fn_item, double_trait_name, fn_name, args)?;
+                quote! {{
+                    #[allow(unreachable_code)]
+                    if false {
+                        std::task::Poll::Ready(#inner)
+                    } else {
+                        std::task::Poll::Pending
+                    }
+                }}
+            }
+            DefaultBodyStrategy::Poll { ready: false, .. } => {
+                quote! { { std::task::Poll::Pending } }
+            }
+            DefaultBodyStrategy::Poll { inner, ready: true } => {
+                let inner = inner.default_body(fn_item, double_trait_name, fn_name, args)?;
+                quote! {{
+                    let inner = #inner;
+                    #[allow(unreachable_code)]
+                    std::task::Poll::Ready(inner)
+                }}
+            }
+            DefaultBodyStrategy::ControlFlow { inner } => {
+                // Like for `Result` we continue with the default of the inner type.
+                let inner = inner.default_body(fn_item, double_trait_name, fn_name, args)?;
+                quote! {{
+                    let inner = #inner;
+                    #[allow(unreachable_code)]
+                    std::ops::ControlFlow::Continue(inner)
+                }}
+            }
+            DefaultBodyStrategy::Option { some } if some.mentions_impl_trait() => {
+                // `None` alone does not tell the compiler which type hides behind `impl Trait`, so
+                // we mention a `Some` we never construct.
+                let inner = some.default_body(fn_item, double_trait_name, fn_name, args)?;
+                quote! {{
+                    #[allow(unreachable_code)]
+                    if false {
+                        Some(#inner)
+                    } else {
+                        None
+                    }
+                }}
+            }
+            DefaultBodyStrategy::Option { .. } => quote! { { None } },
+            DefaultBodyStrategy::Default => quote! { { Default::default() } },
+            DefaultBodyStrategy::Str => quote! { { "" } },
+            // In contrast to `&[]` this does not rely on a coercion, so it also works if the slice
+            // is wrapped, e.g. in a `Result`.
+            DefaultBodyStrategy::Slice => quote! { { Default::default() } },
+            DefaultBodyStrategy::SmartPointer { inner, .. }
+            | DefaultBodyStrategy::Cow { inner }
+                if inner.may_be_unsized() =>
+            {
+                // We can not pass unsized values to e.g. `Box::new`. So we panic right away.
+                let panic = DefaultBodyStrategy::Other.default_body(
+                    fn_item,
+                    double_trait_name,
+                    fn_name,
+                    args,
+                )?;
+                quote! { #panic }
+            }
+            DefaultBodyStrategy::SmartPointer { pointer, inner } => {
+                let inner = inner.default_body(fn_item, double_trait_name, fn_name, args)?;
+                let new = pointer.constructor();
+                quote! {{ #new(#inner) }}
+            }
+            DefaultBodyStrategy::Cow { inner } => {
+                let variant = match **inner {
+                    DefaultBodyStrategy::Str | DefaultBodyStrategy::Slice => quote! { Borrowed },
+                    _ => quote! { Owned },
+                };
+                let inner = inner.default_body(fn_item, double_trait_name, fn_name, args)?;
+                quote! {{ std::borrow::Cow::#variant(#inner) }}
             }
-            DefaultBodyStrategy::Empty => parse2(quote! { { } }).unwrap(),
-            DefaultBodyStrategy::Vec => parse2(quote! { { Vec::new() } }).unwrap(),
-            DefaultBodyStrategy::Option => parse2(quote! { { None } }).unwrap(),
             DefaultBodyStrategy::Result { ok } => {
                 // If the method returns a Result, we provide a default implementation as if it were
                 // infalliable, wrapped in `Ok`.
 
-                let inner = ok.default_body(fn_item, double_trait_name, fn_name);
+                let inner = ok.default_body(fn_item, double_trait_name, fn_name, args)?;
 
                 // We are constructing an empty interator, but we still want to be able to infer an
                 // element type from `#inner` if possible.
-                parse2(quote! {{
+                quote! {{
                     let inner = #inner;
                     #[allow(unreachable_code)]
                     Ok(inner)
-                }})
-                .unwrap()
-            }
-            DefaultBodyStrategy::UnknownImpl => parse2(quote_spanned! {
-                fn_item.sig.output.span() => {
-                    compile_error!(
-                        "impl Trait is currently not supported by double-trait. Apart from the \
-                        special cases of `impl Future` and `impl Stream`."
-                    )
-                }
-            })
-            .unwrap(),
+                }}
+            }
+            DefaultBodyStrategy::UnknownImpl => compile_error_body(
+                fn_item,
+                "impl Trait is currently not supported by double-trait. Apart from the special \
+                cases of `impl Future` and `impl Stream`.",
+            ),
+        };
+        parse_body(fn_item, tokens)
+    }
+}
+
+/// Autoref specialization, uses the `Default` implementation of the type if it exists and falls
+/// back to panicking otherwise.
+fn lenient_default(
+    ty: &Type,
+    fn_item: &TraitItemFn,
+    double_trait_name: Ident,
+    fn_name: Ident,
+    args: &Args,
+) -> syn::Result<TokenStream> {
+    let panic =
+        DefaultBodyStrategy::Other.default_body(fn_item, double_trait_name, fn_name, args)?;
+    let krate = &args.krate;
+    Ok(quote! {{
+        use #krate::__private::{ViaDefault as _, ViaUnimplemented as _};
+        (&#krate::__private::LenientDefault::<#ty>::new()).lenient_default(|| #panic)
+    }})
+}
+
+/// A body emitting a compile error pointing at the entire return type of the method.
+fn compile_error_body(fn_item: &TraitItemFn, message: &str) -> TokenStream {
+    let error = syn::Error::new_spanned(&fn_item.sig.output, message).into_compile_error();
+    quote! {{ #error }}
+}
+
+/// Parses the generated tokens into the body of the default implementation. Should the generated
+/// code be malformed, we report an error pointing at the method, rather than panicking.
+fn parse_body(fn_item: &TraitItemFn, tokens: TokenStream) -> syn::Result<Block> {
+    parse2(tokens).map_err(|error| {
+        syn::Error::new_spanned(
+            &fn_item.sig,
+            format!("double-trait failed to generate a default implementation: {error}"),
+        )
+    })
+}
+
+/// Compact representation for debugging and tests. Types are rendered as source code, rather than
+/// as syntax tree.
+impl fmt::Display for DefaultBodyStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = |inner: &Option<Box<DefaultBodyStrategy>>| match inner {
+            Some(inner) => inner.to_string(),
+            None => "?".to_owned(),
+        };
+        match self {
+            DefaultBodyStrategy::Empty => write!(f, "Empty"),
+            DefaultBodyStrategy::ImplFuture { output } => {
+                write!(f, "ImplFuture {{ output: {} }}", inner(output))
+            }
+            DefaultBodyStrategy::ImplIterator { item } => {
+                write!(f, "ImplIterator {{ item: {} }}", inner(item))
+            }
+            DefaultBodyStrategy::ImplStream { item } => {
+                write!(f, "ImplStream {{ item: {} }}", inner(item))
+            }
+            DefaultBodyStrategy::Result { ok } => write!(f, "Result {{ ok: {ok} }}"),
+            DefaultBodyStrategy::Option { some } => write!(f, "Option {{ some: {some} }}"),
+            DefaultBodyStrategy::Vec => write!(f, "Vec"),
+            DefaultBodyStrategy::Poll { inner, ready } => {
+                write!(f, "Poll {{ inner: {inner}, ready: {ready} }}")
+            }
+            DefaultBodyStrategy::ControlFlow { inner } => {
+                write!(f, "ControlFlow {{ inner: {inner} }}")
+            }
+            DefaultBodyStrategy::Ordering => write!(f, "Ordering"),
+            DefaultBodyStrategy::SmartPointer { pointer, inner } => {
+                write!(f, "{pointer} {{ inner: {inner} }}")
+            }
+            DefaultBodyStrategy::Cow { inner } => write!(f, "Cow {{ inner: {inner} }}"),
+            DefaultBodyStrategy::UnknownImpl => write!(f, "UnknownImpl"),
+            DefaultBodyStrategy::UnknownType(ty) => write!(f, "UnknownType({})", quote! { #ty }),
+            DefaultBodyStrategy::AssociatedType(ty) => {
+                write!(f, "AssociatedType({})", quote! { #ty })
+            }
+            DefaultBodyStrategy::SelfType => write!(f, "SelfType"),
+            DefaultBodyStrategy::Str => write!(f, "Str"),
+            DefaultBodyStrategy::Slice => write!(f, "Slice"),
+            DefaultBodyStrategy::Reference(reference) => {
+                write!(f, "Reference({})", quote! { #reference })
+            }
+            DefaultBodyStrategy::Leak(reference) => write!(f, "Leak({})", quote! { #reference }),
+            DefaultBodyStrategy::Default => write!(f, "Default"),
+            DefaultBodyStrategy::Other => write!(f, "Other"),
         }
     }
 }
 
-pub fn default_body_strategy(output: &ReturnType) -> DefaultBodyStrategy {
+pub fn default_body_strategy(output: &ReturnType) -> syn::Result<DefaultBodyStrategy> {
     if let ReturnType::Type(_rarrow, ty) = output {
         type_info(ty)
     } else {
-        DefaultBodyStrategy::Empty
+        Ok(DefaultBodyStrategy::Empty)
     }
 }
 
-fn type_info(ty: &Type) -> DefaultBodyStrategy {
-    match *ty {
+fn type_info(ty: &Type) -> syn::Result<DefaultBodyStrategy> {
+    let strategy = match *ty {
         Type::ImplTrait(ref impl_trait) => {
             let mut trait_bounds = impl_trait.bounds.iter().filter_map(|b| match b {
                 TypeParamBound::Trait(trait_bound) => Some(trait_bound),
@@ -151,33 +646,31 @@ fn type_info(ty: &Type) -> DefaultBodyStrategy {
                 | TypeParamBound::Verbatim(_)
                 | _ => None,
             });
-            let first_trait_bound = trait_bounds
-                .next()
-                .expect("At least one trait bound expected in impl trait.");
-            let first_path_segment = first_trait_bound
-                .path
-                .segments
-                .first()
-                .expect("There must be at least one path segment in trait bound");
+            let first_trait_bound = trait_bounds.next().ok_or_else(|| {
+                syn::Error::new_spanned(impl_trait, "expected at least one trait bound")
+            })?;
+            let first_path_segment = first_trait_bound.path.segments.first().ok_or_else(|| {
+                syn::Error::new_spanned(first_trait_bound, "expected a path to a trait")
+            })?;
             let identifier = &first_path_segment.ident.to_string();
             match identifier.as_str() {
                 "Future" => {
                     let output = assoctiated_type(&first_path_segment.arguments, "Output");
                     // If the first trait bound is Future, we assume that this is an impl Future.
                     DefaultBodyStrategy::ImplFuture {
-                        output: output.map(|ty| Box::new(type_info(ty))),
+                        output: boxed_type_info(output)?,
                     }
                 }
                 "Iterator" => {
                     let item = assoctiated_type(&first_path_segment.arguments, "Item");
                     DefaultBodyStrategy::ImplIterator {
-                        item: item.map(|ty| Box::new(type_info(ty))),
+                        item: boxed_type_info(item)?,
                     }
                 }
                 "Stream" => {
                     let item = assoctiated_type(&first_path_segment.arguments, "Item");
                     DefaultBodyStrategy::ImplStream {
-                        _item: item.map(|ty| Box::new(type_info(ty))),
+                        item: boxed_type_info(item)?,
                     }
                 }
                 _ => DefaultBodyStrategy::UnknownImpl,
@@ -187,39 +680,260 @@ fn type_info(ty: &Type) -> DefaultBodyStrategy {
             if tuple_type.elems.is_empty() {
                 DefaultBodyStrategy::Empty
             } else {
-                DefaultBodyStrategy::Other
+                unknown_type(ty)
             }
         }
+        Type::Path(ref type_path)
+            if type_path.qself.is_none() && type_path.path.is_ident("Self") =>
+        {
+            DefaultBodyStrategy::SelfType
+        }
+        Type::Path(ref type_path) if is_associated_type_of_self(type_path) => {
+            DefaultBodyStrategy::AssociatedType(Box::new(ty.clone()))
+        }
         Type::Path(ref type_path) => {
             let Some(last) = type_path.path.segments.last() else {
-                return DefaultBodyStrategy::Other;
+                return Ok(unknown_type(ty));
             };
-            if last.ident.to_string() == "Option" {
-                return DefaultBodyStrategy::Option;
+            if last.ident == "Option" {
+                let some = match first_type_argument(&last.arguments) {
+                    Some(some) => type_info(some)?,
+                    None => DefaultBodyStrategy::Other,
+                };
+                return Ok(DefaultBodyStrategy::Option {
+                    some: Box::new(some),
+                });
             }
-            if last.ident.to_string() == "Vec" {
-                return DefaultBodyStrategy::Vec;
+            if last.ident == "Vec" {
+                return Ok(DefaultBodyStrategy::Vec);
             }
-            if last.ident.to_string() != "Result" {
-                return DefaultBodyStrategy::Other;
+            if last.ident == "Poll" {
+                let inner = match first_type_argument(&last.arguments) {
+                    Some(inner) => type_info(inner)?,
+                    None => DefaultBodyStrategy::Other,
+                };
+                return Ok(DefaultBodyStrategy::Poll {
+                    inner: Box::new(inner),
+                    ready: false,
+                });
+            }
+            if last.ident == "ControlFlow" {
+                return control_flow(&last.arguments);
+            }
+            if is_cmp_ordering(type_path) {
+                return Ok(DefaultBodyStrategy::Ordering);
+            }
+            if let Some(pointer) = SmartPointer::from_ident(&last.ident) {
+                return smart_pointer(ty, pointer, &last.arguments);
+            }
+            if last.ident == "Cow" {
+                return cow(ty, &last.arguments);
+            }
+            if last.ident != "Result" {
+                return Ok(unknown_type(ty));
             }
             let PathArguments::AngleBracketed(ref generic_arguments) = last.arguments else {
-                return DefaultBodyStrategy::Other;
+                return Ok(unknown_type(ty));
             };
             let Some(generic_argument) = generic_arguments.args.first() else {
-                return DefaultBodyStrategy::Other;
+                return Ok(unknown_type(ty));
             };
             let GenericArgument::Type(ok) = generic_argument else {
-                return DefaultBodyStrategy::Other;
+                return Ok(unknown_type(ty));
             };
             DefaultBodyStrategy::Result {
-                ok: Box::new(type_info(ok)),
+                ok: Box::new(type_info(ok)?),
+            }
+        }
+        Type::Reference(ref reference) => match *reference.elem {
+            Type::Path(ref pointee)
+                if reference.mutability.is_none() && pointee.path.is_ident("str") =>
+            {
+                DefaultBodyStrategy::Str
             }
+            Type::Slice(_) => DefaultBodyStrategy::Slice,
+            // Only sized types can be leaked, this excludes e.g. `dyn Trait`.
+            Type::Path(ref pointee)
+                if !pointee.path.is_ident("str") && !mentions_impl_trait(quote! { #pointee }) =>
+            {
+                DefaultBodyStrategy::Reference(Box::new(reference.clone()))
+            }
+            _ => unknown_type(ty),
+        },
+        // We can not name these types in a generic argument.
+        Type::Never(_) | Type::Infer(_) | Type::Verbatim(_) => DefaultBodyStrategy::Other,
+        _ => unknown_type(ty),
+    };
+    Ok(strategy)
+}
+
+/// Smart pointers owning a single value, which we know how to construct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmartPointer {
+    Box,
+    Rc,
+    Arc,
+}
+
+impl SmartPointer {
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        if ident == "Box" {
+            Some(SmartPointer::Box)
+        } else if ident == "Rc" {
+            Some(SmartPointer::Rc)
+        } else if ident == "Arc" {
+            Some(SmartPointer::Arc)
+        } else {
+            None
+        }
+    }
+
+    /// Path to the function wrapping a value in the pointer.
+    fn constructor(self) -> TokenStream {
+        match self {
+            SmartPointer::Box => quote! { Box::new },
+            SmartPointer::Rc => quote! { std::rc::Rc::new },
+            SmartPointer::Arc => quote! { std::sync::Arc::new },
+        }
+    }
+}
+
+impl fmt::Display for SmartPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Strategy for e.g. `Box<T>`. Only worth it, if we know how to construct `T`. Otherwise we treat
+/// the pointer like any other unknown type, e.g. to panic right away for `Box<dyn Error>`.
+fn smart_pointer(
+    ty: &Type,
+    pointer: SmartPointer,
+    arguments: &PathArguments,
+) -> syn::Result<DefaultBodyStrategy> {
+    let Some(inner) = first_type_argument(arguments) else {
+        return Ok(unknown_type(ty));
+    };
+    let strategy = match type_info(inner)? {
+        DefaultBodyStrategy::UnknownType(_)
+        | DefaultBodyStrategy::UnknownImpl
+        | DefaultBodyStrategy::Other => unknown_type(ty),
+        inner => DefaultBodyStrategy::SmartPointer {
+            pointer,
+            inner: Box::new(inner),
+        },
+    };
+    Ok(strategy)
+}
+
+/// Strategy for `Cow<'_, B>`. `str` and slices are borrowed, other types need to be constructed.
+fn cow(ty: &Type, arguments: &PathArguments) -> syn::Result<DefaultBodyStrategy> {
+    let Some(inner) = first_type_argument(arguments) else {
+        return Ok(unknown_type(ty));
+    };
+    let inner = match inner {
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+            DefaultBodyStrategy::Str
+        }
+        Type::Slice(_) => DefaultBodyStrategy::Slice,
+        inner => type_info(inner)?,
+    };
+    let strategy = match inner {
+        DefaultBodyStrategy::UnknownType(_)
+        | DefaultBodyStrategy::UnknownImpl
+        | DefaultBodyStrategy::Other => unknown_type(ty),
+        inner => DefaultBodyStrategy::Cow {
+            inner: Box::new(inner),
+        },
+    };
+    Ok(strategy)
+}
+
+/// `true` for `cmp::Ordering` and `std::cmp::Ordering`. A bare `Ordering` could just as well be
+/// `std::sync::atomic::Ordering`, so we do not guess.
+fn is_cmp_ordering(type_path: &TypePath) -> bool {
+    if type_path.qself.is_some() {
+        return false;
+    }
+    let segments: Vec<_> = type_path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    matches!(
+        &segments[..],
+        [cmp, ordering] if cmp == "cmp" && ordering == "Ordering"
+    ) || matches!(
+        &segments[..],
+        [krate, cmp, ordering]
+            if (krate == "std" || krate == "core") && cmp == "cmp" && ordering == "Ordering"
+    )
+}
+
+/// Strategy for `ControlFlow<B, C>`. `C` defaults to `()`.
+fn control_flow(arguments: &PathArguments) -> syn::Result<DefaultBodyStrategy> {
+    let continue_type = match arguments {
+        PathArguments::AngleBracketed(generic_arguments) => generic_arguments
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .nth(1),
+        _ => None,
+    };
+    let inner = match continue_type {
+        Some(ty) => type_info(ty)?,
+        None => DefaultBodyStrategy::Empty,
+    };
+    Ok(DefaultBodyStrategy::ControlFlow {
+        inner: Box::new(inner),
+    })
+}
+
+/// The first generic type argument, skipping lifetimes. E.g. `str` in `Cow<'a, str>`.
+fn first_type_argument(arguments: &PathArguments) -> Option<&Type> {
+    let PathArguments::AngleBracketed(generic_arguments) = arguments else {
+        return None;
+    };
+    generic_arguments.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Strategy for an optional associated type, like the `Item` of an `impl Iterator`.
+fn boxed_type_info(ty: Option<&Type>) -> syn::Result<Option<Box<DefaultBodyStrategy>>> {
+    ty.map(|ty| type_info(ty).map(Box::new)).transpose()
+}
+
+/// `true` for `Self::Item` or `<Self as Trait>::Item`.
+fn is_associated_type_of_self(type_path: &TypePath) -> bool {
+    match &type_path.qself {
+        Some(qself) => matches!(&*qself.ty, Type::Path(ty) if ty.path.is_ident("Self")),
+        None => {
+            let segments = &type_path.path.segments;
+            segments.len() == 2 && segments[0].ident == "Self"
         }
-        _ => DefaultBodyStrategy::Other,
     }
 }
 
+fn unknown_type(ty: &Type) -> DefaultBodyStrategy {
+    DefaultBodyStrategy::UnknownType(Box::new(ty.clone()))
+}
+
+/// `true` if the tokens contain `impl Trait`, e.g. `Box<impl Display>`. Such types can not be named
+/// in the body of the default implementation.
+fn mentions_impl_trait(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "impl",
+        TokenTree::Group(group) => mentions_impl_trait(group.stream()),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
+}
+
 /// Find the associated output type of an impl Future trait. E.g. the `i64` in impl Future<Output=i64>.
 fn assoctiated_type<'a>(
     future_trait_args: &'a PathArguments,
@@ -247,14 +961,174 @@ fn assoctiated_type<'a>(
 #[cfg(test)]
 mod tests {
     use super::{DefaultBodyStrategy, default_body_strategy};
+    use crate::args::Mode;
     use quote::quote;
-    use syn::{ReturnType, parse2};
+    use syn::{ReturnType, Type, TypeImplTrait, TypeParamBound, parse2};
+
+    #[test]
+    fn describe_result_option() {
+        let rt: ReturnType = parse2(quote! {-> Result<Option<i32>, MyError> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!("returns `Ok` of `None`", strategy.describe(Mode::Default));
+        assert_eq!("panics", strategy.describe(Mode::Strict));
+    }
+
+    #[test]
+    fn describe_unknown_type() {
+        let rt: ReturnType = parse2(quote! {-> impl Future<Output = i32> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!("panics", strategy.describe(Mode::Default));
+        assert_eq!(
+            "returns a future resolving to `Default::default()` if implemented",
+            strategy.describe(Mode::Lenient)
+        );
+    }
+
+    #[test]
+    fn display_strategy() {
+        let rt: ReturnType = parse2(quote! {-> Result<Vec<u8>, Box<dyn Error> > }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!("Result { ok: Vec }", strategy.to_string());
+
+        let rt: ReturnType = parse2(quote! {-> impl Future<Output = (u8, u8)> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!(
+            "ImplFuture { output: UnknownType((u8 , u8)) }",
+            strategy.to_string()
+        );
+    }
+
+    #[test]
+    fn resolve_associated_types_of_self() {
+        let rt: ReturnType = parse2(quote! {-> Result<Self::Output, MyError> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!(
+            "Result { ok: AssociatedType(Self :: Output) }",
+            strategy.to_string()
+        );
+        assert!(strategy.panics_immediately(Mode::Default));
+
+        let resolved = strategy.resolve_for_dummy().unwrap();
+        assert_eq!("Result { ok: Default }", resolved.to_string());
+        assert!(!resolved.panics_immediately(Mode::Default));
+
+        let rt: ReturnType = parse2(quote! {-> Option<Self::Output> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert!(strategy.resolve_for_dummy().is_none());
+    }
+
+    #[test]
+    fn resolve_self_for_dummy() {
+        let rt: ReturnType = parse2(quote! {-> Box<Self> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!("Box { inner: SelfType }", strategy.to_string());
+        assert_eq!("panics", strategy.describe(Mode::Default));
+
+        let resolved = strategy.resolve_for_dummy().unwrap();
+        assert_eq!(
+            "returns a boxed `Default::default()`",
+            resolved.describe(Mode::Default)
+        );
+
+        let rt: ReturnType = parse2(quote! {-> Box<dyn Error> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!("UnknownType(Box < dyn Error >)", strategy.to_string());
+    }
+
+    #[test]
+    fn borrowed_return_types() {
+        let strategy = |tokens| default_body_strategy(&parse2(tokens).unwrap()).unwrap();
+
+        assert_eq!("Str", strategy(quote! {-> &'a str }).to_string());
+        assert_eq!("Slice", strategy(quote! {-> &mut [u8] }).to_string());
+        assert_eq!(
+            "Option { some: Reference(& Config) }",
+            strategy(quote! {-> Option<&Config> }).to_string()
+        );
+        let reference = strategy(quote! {-> &Config });
+        assert_eq!("Reference(& Config)", reference.to_string());
+        assert_eq!(
+            "Leak(& Config)",
+            reference.resolve_for_dummy().unwrap().to_string()
+        );
+        assert_eq!(
+            "UnknownType(& dyn Error)",
+            strategy(quote! {-> &dyn Error }).to_string()
+        );
+    }
+
+    #[test]
+    fn smart_pointers_wrap_inner_strategy() {
+        let strategy = |tokens| default_body_strategy(&parse2(tokens).unwrap()).unwrap();
+
+        assert_eq!(
+            "Arc { inner: Option { some: UnknownType(Config) } }",
+            strategy(quote! {-> std::sync::Arc<Option<Config>> }).to_string()
+        );
+        assert_eq!(
+            "ImplFuture { output: Rc { inner: Result { ok: Vec } } }",
+            strategy(quote! {-> impl Future<Output = Rc<Result<Vec<u8>, E>>> }).to_string()
+        );
+        let cow = strategy(quote! {-> Cow<'_, str> });
+        assert_eq!("Cow { inner: Str }", cow.to_string());
+        assert_eq!("returns a `Cow` of `\"\"`", cow.describe(Mode::Lenient));
+        assert_eq!(
+            "UnknownType(Rc < RefCell < State > >)",
+            strategy(quote! {-> Rc<RefCell<State>> }).to_string()
+        );
+    }
+
+    #[test]
+    fn option_of_impl_trait() {
+        let rt: ReturnType =
+            parse2(quote! {-> Result<Option<impl Future<Output = i32> >, E> }).unwrap();
+        let strategy = default_body_strategy(&rt).unwrap();
+        assert_eq!(
+            "Result { ok: Option { some: ImplFuture { output: UnknownType(i32) } } }",
+            strategy.to_string()
+        );
+        assert_eq!("returns `Ok` of `None`", strategy.describe(Mode::Default));
+    }
+
+    #[test]
+    fn poll_control_flow_and_ordering() {
+        let strategy = |tokens| default_body_strategy(&parse2(tokens).unwrap()).unwrap();
+
+        let mut poll = strategy(quote! {-> Poll<Option<Result<Vec<u8>, E>>> });
+        assert_eq!("returns `Poll::Pending`", poll.describe(Mode::Default));
+        poll.poll_ready();
+        assert_eq!(
+            "returns `Poll::Ready` of `None`",
+            poll.describe(Mode::Default)
+        );
+        assert_eq!(
+            "ControlFlow { inner: Empty }",
+            strategy(quote! {-> ControlFlow<String> }).to_string()
+        );
+        assert_eq!(
+            "ControlFlow { inner: Vec }",
+            strategy(quote! {-> std::ops::ControlFlow<String, Vec<u8>> }).to_string()
+        );
+        assert_eq!(
+            "Ordering",
+            strategy(quote! {-> std::cmp::Ordering }).to_string()
+        );
+        assert_eq!("Ordering", strategy(quote! {-> cmp::Ordering }).to_string());
+        assert_eq!(
+            "UnknownType(Ordering)",
+            strategy(quote! {-> Ordering }).to_string()
+        );
+        assert_eq!(
+            "UnknownType(atomic :: Ordering)",
+            strategy(quote! {-> atomic::Ordering }).to_string()
+        );
+    }
 
     #[test]
     fn return_type_info_unit() {
         let rt: ReturnType = parse2(quote! {-> () }).unwrap();
         assert!(matches!(
-            default_body_strategy(&rt),
+            default_body_strategy(&rt).unwrap(),
             DefaultBodyStrategy::Empty
         ));
     }
@@ -263,8 +1137,8 @@ mod tests {
     fn return_type_info_i32() {
         let rt: ReturnType = parse2(quote! {-> i32 }).unwrap();
         assert!(matches!(
-            default_body_strategy(&rt),
-            DefaultBodyStrategy::Other
+            default_body_strategy(&rt).unwrap(),
+            DefaultBodyStrategy::UnknownType(_)
         ));
     }
 
@@ -272,8 +1146,8 @@ mod tests {
     fn return_type_info_option_i32() {
         let rt: ReturnType = parse2(quote! {-> Option<i32> }).unwrap();
         assert!(matches!(
-            default_body_strategy(&rt),
-            DefaultBodyStrategy::Option
+            default_body_strategy(&rt).unwrap(),
+            DefaultBodyStrategy::Option { .. }
         ));
     }
 
@@ -281,7 +1155,7 @@ mod tests {
     fn return_type_info_vec_i32() {
         let rt: ReturnType = parse2(quote! {-> Vec<i32> }).unwrap();
         assert!(matches!(
-            default_body_strategy(&rt),
+            default_body_strategy(&rt).unwrap(),
             DefaultBodyStrategy::Vec
         ));
     }
@@ -291,17 +1165,17 @@ mod tests {
         let rt: ReturnType = parse2(quote! {-> impl Future<Output = i32> }).unwrap();
         let DefaultBodyStrategy::ImplFuture {
             output: Some(output),
-        } = default_body_strategy(&rt)
+        } = default_body_strategy(&rt).unwrap()
         else {
             panic!("Expected ReturnTypeInfo::ImplFuture with Some output");
         };
-        assert!(matches!(*output, DefaultBodyStrategy::Other));
+        assert!(matches!(*output, DefaultBodyStrategy::UnknownType(_)));
     }
 
     #[test]
     fn return_type_info_result_unit() {
         let rt: ReturnType = parse2(quote! {-> Result<(), MyError> }).unwrap();
-        let DefaultBodyStrategy::Result { ok } = default_body_strategy(&rt) else {
+        let DefaultBodyStrategy::Result { ok } = default_body_strategy(&rt).unwrap() else {
             panic!("Expected ReturnTypeInfo::Result");
         };
         assert!(matches!(*ok, DefaultBodyStrategy::Empty));
@@ -310,11 +1184,15 @@ mod tests {
     #[test]
     fn return_type_info_result_vec() {
         let rt: ReturnType = parse2(quote! {-> Result<Vec<i32>, MyError> }).unwrap();
-        let rti = default_body_strategy(&rt);
-        let expected = DefaultBodyStrategy::Result {
-            ok: Box::new(DefaultBodyStrategy::Vec),
+        let rti = default_body_strategy(&rt).unwrap();
+        let DefaultBodyStrategy::Result { ok } = &rti else {
+            panic!("Expected DefaultBodyStrategy::Result");
         };
-        assert_eq!(expected, rti);
+        assert!(matches!(**ok, DefaultBodyStrategy::Vec));
+        assert_eq!(
+            "returns `Ok` of an empty `Vec`",
+            rti.describe(Mode::Default)
+        );
     }
 
     #[test]
@@ -322,7 +1200,7 @@ mod tests {
         let rt: ReturnType = parse2(quote! {-> impl Future<Output = ()> }).unwrap();
         let DefaultBodyStrategy::ImplFuture {
             output: Some(output),
-        } = default_body_strategy(&rt)
+        } = default_body_strategy(&rt).unwrap()
         else {
             panic!("Expected ReturnTypeInfo::ImplFuture with Some output");
         };
@@ -335,7 +1213,7 @@ mod tests {
             parse2(quote! {-> impl Future<Output = impl Iterator<Item=i32>> }).unwrap();
         let DefaultBodyStrategy::ImplFuture {
             output: Some(output),
-        } = default_body_strategy(&rt)
+        } = default_body_strategy(&rt).unwrap()
         else {
             panic!("Expected ReturnTypeInfo::ImplFuture with Some output");
         };
@@ -344,4 +1222,39 @@ mod tests {
             DefaultBodyStrategy::ImplIterator { item: Some(_) }
         ));
     }
+
+    // Neither `rustc` nor `syn` parse these types, so these errors can not be covered by UI tests.
+    // We construct them by hand instead.
+    #[test]
+    fn error_for_impl_without_trait_bound() {
+        let mut impl_trait: TypeImplTrait = parse2(quote! { impl Send + 'static }).unwrap();
+        impl_trait.bounds = impl_trait
+            .bounds
+            .into_iter()
+            .filter(|bound| matches!(bound, TypeParamBound::Lifetime(_)))
+            .collect();
+        let rt = ReturnType::Type(Default::default(), Box::new(Type::ImplTrait(impl_trait)));
+
+        let Err(error) = default_body_strategy(&rt) else {
+            panic!("Expected an error");
+        };
+
+        assert_eq!("expected at least one trait bound", error.to_string());
+    }
+
+    #[test]
+    fn error_for_trait_bound_without_path() {
+        let mut impl_trait: TypeImplTrait = parse2(quote! { impl Iterator<Item = i32> }).unwrap();
+        let Some(TypeParamBound::Trait(bound)) = impl_trait.bounds.first_mut() else {
+            panic!("Expected a trait bound");
+        };
+        bound.path.segments.clear();
+        let rt = ReturnType::Type(Default::default(), Box::new(Type::ImplTrait(impl_trait)));
+
+        let Err(error) = default_body_strategy(&rt) else {
+            panic!("Expected an error");
+        };
+
+        assert_eq!("expected a path to a trait", error.to_string());
+    }
 }
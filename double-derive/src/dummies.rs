@@ -1,30 +1,124 @@
-use quote::quote;
-use syn::ItemTrait;
-
-use crate::{double_trait::double_trait, dummy_impl::dummy_impl};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{Ident, ItemTrait, parse2};
+
+use crate::{
+    args::{Args, DummyArg},
+    double_trait::{GeneratedDefault, double_trait},
+    dummy_impl::dummy_impl,
+    dummy_struct::dummy_struct,
+    dyn_compatibility::{check_dyn_compatible, into_dyn_impl},
+    fill::{fill, fill_macro, strip_method_args},
+};
 
 /// The main implementation of [`crate::dummies`]. This function is not annotated with
 /// `#[proc_macro_attribute]` so it can exist in unit tests. It uses only APIs build on top of
 /// [`proc_macro2`] in order to be unit testable.
-pub fn expand(org_trait: ItemTrait) -> syn::Result<proc_macro2::TokenStream> {
-    let trait_with_dummies = double_trait(org_trait.clone())?;
-    let dummy_impl = dummy_impl(org_trait.ident.clone(), org_trait);
+pub fn expand(org_trait: ItemTrait, args: &Args) -> syn::Result<TokenStream> {
+    let org_trait_ident = org_trait.ident.clone();
+    if let Some(unsafety) = &org_trait.unsafety
+        && !args.allow_unsafe
+        && args.dummy_type(&org_trait.ident).is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            unsafety,
+            "implementing an unsafe trait for the dummy asserts that the dummy upholds its safety \
+            contract. Opt in using `#[dummies(allow_unsafe)]` or skip the implementation using \
+            `#[dummies(no_dummy)]`",
+        ));
+    }
+    let (trait_with_dummies, defaults) = double_trait(org_trait.clone(), args)?;
+    let trait_with_dummies = if args.fill {
+        // The trait stays free of default implementations. Instead impls annotated with `fill`
+        // receive them.
+        let org_trait_without_args = strip_method_args(org_trait.clone())?;
+        let fill_macro = fill_macro(&org_trait, args);
+        quote! {
+            #org_trait_without_args
+
+            #fill_macro
+        }
+    } else {
+        quote! { #trait_with_dummies }
+    };
+    let dummy_struct = args
+        .dummy_struct(&org_trait.ident)
+        .map(|name| dummy_struct(&name, &org_trait));
+    let into_dyn = if args.dyn_compatible {
+        check_dyn_compatible(&org_trait)?;
+        // We only know the shared and the generated dummies to be thread safe
+        let thread_safe = matches!(args.dummy, DummyArg::Shared | DummyArg::Struct(_));
+        args.dummy_type(&org_trait.ident)
+            .map(|dummy| into_dyn_impl(&org_trait.ident, &dummy, &args.krate, thread_safe))
+    } else {
+        None
+    };
+    let overrides: Vec<_> = defaults
+        .iter()
+        .filter_map(GeneratedDefault::dummy_override)
+        .cloned()
+        .collect();
+    let dummy_impl = args.dummy_type(&org_trait.ident).map(|dummy| {
+        dummy_impl(
+            org_trait.ident.clone(),
+            org_trait.clone(),
+            &dummy,
+            overrides,
+        )
+    });
+    let dummy_impl = match dummy_impl {
+        Some(dummy_impl) if args.fill => {
+            let filled = fill(org_trait, parse2(dummy_impl)?, args)?;
+            Some(quote! { #filled })
+        }
+        dummy_impl => dummy_impl,
+    };
 
-    let token_stream = quote! {
+    let mut token_stream = quote! {
         #trait_with_dummies
 
+        #dummy_struct
+
         #dummy_impl
+
+        #into_dyn
     };
+    if args.debug {
+        let debug_note = debug_note(&org_trait_ident, &token_stream, &defaults);
+        token_stream.extend(debug_note);
+    }
     Ok(token_stream)
 }
 
+/// Proc macros can not emit warnings on stable Rust. Instead we emit a deprecated item and use it
+/// right away, so the compiler prints the deprecation note as a warning pointing at the trait.
+fn debug_note(
+    trait_name: &Ident,
+    expansion: &TokenStream,
+    defaults: &[GeneratedDefault],
+) -> TokenStream {
+    let mut note = format!("expansion of `dummies` for `{trait_name}`:\n{expansion}\n");
+    note.push_str("default body strategies:\n");
+    for default in defaults {
+        note.push_str(&format!("  {}\n", default.debug_line()));
+    }
+    quote_spanned! { trait_name.span() =>
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct DummiesDebug;
+            let _ = DummiesDebug;
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use quote::quote;
-    use syn::{ItemTrait, parse2};
+    use syn::{ItemTrait, parse_quote, parse2};
 
     use super::expand;
+    use crate::args::{Args, DummyArg};
 
     #[test]
     fn private_empty_trait() {
@@ -34,13 +128,13 @@ mod tests {
         });
 
         // When expanded with `dummies`
-        let output = expand(empty_trait).unwrap();
+        let output = expand(empty_trait, &Args::default()).unwrap();
 
         // Then it will be unchanged
         let expected = quote! {
             trait MyTrait{}
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string())
     }
@@ -51,13 +145,13 @@ mod tests {
         let org_trait = given(quote! { pub trait MyTrait {} });
 
         // When generating the dummy
-        let output = expand(org_trait).unwrap();
+        let output = expand(org_trait, &Args::default()).unwrap();
 
         // Then the generated trait should be public, too
         let expected = quote! {
             pub trait MyTrait {}
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -72,15 +166,22 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should contain a default implementation doing nothing
         let expected = quote! {
             trait MyTrait {
-                fn foobar(&self) {}
+                fn foobar(&self) {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(foobar);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                }
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -95,7 +196,7 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should contain a default implementation calling unimplemented!()
         let expected = quote! {
@@ -103,11 +204,12 @@ mod tests {
                 fn foobar(&self) -> i32 {
                     let double_trait_name = stringify!(MyTrait);
                     let fn_name = stringify!(foobar);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
                     unimplemented!("{double_trait_name}::{fn_name}")
                 }
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -122,20 +224,26 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should contain a default implementation doing nothing evaluating
         // to Ok(())
         let expected = quote! {
             trait MyTrait {
                 fn foobar(&self) -> Result<(), Box<dyn Error> > {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(foobar);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     let inner = {};
                     #[allow(unreachable_code)]
                     Ok(inner)
                 }
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -150,20 +258,26 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should contain a default implementation doing nothing evaluating
         // to Ok(())
         let expected = quote! {
             trait MyTrait {
                 fn foobar(&self) -> Result<Vec<i32>, Box<dyn Error> > {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(foobar);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
                     let inner = { Vec::new() };
                     #[allow(unreachable_code)]
                     Ok(inner)
                 }
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -178,7 +292,7 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should not overide the existing default
         let expected = quote! {
@@ -186,7 +300,7 @@ mod tests {
                 fn foobar() { println!("Hello Default!") }
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for double_trait::__private::Dummy {}
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
@@ -201,19 +315,346 @@ mod tests {
         });
 
         // When generating the dummy
-        let output = expand(item).unwrap();
+        let output = expand(item, &Args::default()).unwrap();
 
         // Then the generated trait should contain an empty default implementation
         let expected = quote! {
             trait MyTrait {
-                async fn foobar(&self) {}
+                async fn foobar(&self) {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(foobar);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                }
+            }
+
+            impl MyTrait for double_trait::__private::Dummy {}
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn custom_crate_path() {
+        // Given a trait with an associated type
+        let item = given(quote! {
+            trait MyTrait {
+                type Item;
+            }
+        });
+
+        // When expanded with `dummies(crate = my_facade::double_trait)`
+        let args = Args {
+            krate: parse_quote!(my_facade::double_trait),
+            ..Args::default()
+        };
+        let output = expand(item, &args).unwrap();
+
+        // Then the generated code refers to the runtime crate via the custom path
+        let expected = quote! {
+            trait MyTrait {
+                type Item;
             }
 
-            impl MyTrait for double_trait::Dummy {}
+            impl MyTrait for my_facade::double_trait::__private::Dummy {
+                type Item = my_facade::double_trait::__private::Dummy;
+            }
         };
         assert_eq!(expected.to_string(), output.to_string());
     }
 
+    #[test]
+    fn no_dummy() {
+        // Given an empty trait
+        let item = given(quote! { trait MyTrait {} });
+
+        // When expanded with `dummies(no_dummy)`
+        let args = Args {
+            dummy: DummyArg::Skip,
+            ..Args::default()
+        };
+        let output = expand(item, &args).unwrap();
+
+        // Then no implementation for `Dummy` is generated
+        let expected = quote! { trait MyTrait {} };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn custom_dummy() {
+        // Given a trait with an associated type
+        let item = given(quote! {
+            trait MyTrait {
+                type Item;
+            }
+        });
+
+        // When expanded with `dummies(dummy = MyDummy)`
+        let args = Args {
+            dummy: DummyArg::Custom(Box::new(parse_quote!(MyDummy))),
+            ..Args::default()
+        };
+        let output = expand(item, &args).unwrap();
+
+        // Then the trait is implemented for `MyDummy` instead, which is also used for the
+        // associated type
+        let expected = quote! {
+            trait MyTrait {
+                type Item;
+            }
+
+            impl MyTrait for MyDummy {
+                type Item = MyDummy;
+            }
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn dedicated_dummy_struct() {
+        // Given a public trait
+        let item = given(quote! {
+            pub trait MyTrait {
+                fn foobar(&self);
+            }
+        });
+
+        // When expanded with `dummies(dummy_struct)`
+        let args = Args {
+            dummy: DummyArg::Struct(None),
+            ..Args::default()
+        };
+        let output = expand(item, &args).unwrap();
+
+        // Then a public struct `MyTraitDummy` is generated, which implements `MyTrait` and can be
+        // converted into a trait object
+        let expected = quote! {
+            pub trait MyTrait {
+                fn foobar(&self) {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(foobar);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                }
+            }
+
+            #[derive(Debug, Default, Clone)]
+            pub struct MyTraitDummy;
+
+            impl From<MyTraitDummy> for Box<dyn MyTrait> {
+                fn from(dummy: MyTraitDummy) -> Self {
+                    Box::new(dummy)
+                }
+            }
+
+            impl MyTrait for MyTraitDummy {}
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn dyn_compatible_trait_can_be_converted_into_trait_object() {
+        // Given an empty trait
+        let item = given(quote! { trait MyTrait {} });
+
+        // When expanded with `dummies(dyn_compatible)`
+        let args = Args {
+            dyn_compatible: true,
+            ..Args::default()
+        };
+        let output = expand(item, &args).unwrap();
+
+        // Then `Dummy` can be converted into `dyn MyTrait`
+        let expected = quote! {
+            trait MyTrait {}
+
+            impl MyTrait for double_trait::__private::Dummy {}
+
+            impl double_trait::__private::IntoDyn<dyn MyTrait> for double_trait::__private::Dummy {
+                fn into_boxed(self) -> Box<dyn MyTrait> {
+                    Box::new(self)
+                }
+
+                fn into_arc(self) -> std::sync::Arc<dyn MyTrait> {
+                    std::sync::Arc::new(self)
+                }
+
+                fn into_rc(self) -> std::rc::Rc<dyn MyTrait> {
+                    std::rc::Rc::new(self)
+                }
+            }
+
+            impl double_trait::__private::IntoDyn<dyn MyTrait + Send + Sync>
+                for double_trait::__private::Dummy
+            {
+                fn into_boxed(self) -> Box<dyn MyTrait + Send + Sync> {
+                    Box::new(self)
+                }
+
+                fn into_arc(self) -> std::sync::Arc<dyn MyTrait + Send + Sync> {
+                    std::sync::Arc::new(self)
+                }
+
+                fn into_rc(self) -> std::rc::Rc<dyn MyTrait + Send + Sync> {
+                    std::rc::Rc::new(self)
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn error_if_dyn_compatible_is_requested_for_incompatible_trait() {
+        // Given a trait with a method returning `Self`
+        let item = given(quote! {
+            trait MyTrait {
+                fn duplicate(&self) -> Self;
+            }
+        });
+
+        // When expanded with `dummies(dyn_compatible)`
+        let args = Args {
+            dyn_compatible: true,
+            ..Args::default()
+        };
+        let result = expand(item, &args);
+
+        // Then an error explains why the trait is not dyn compatible
+        let Err(error) = result else {
+            panic!("Expected error for dyn incompatible trait");
+        };
+        assert_eq!(
+            "trait is not dyn compatible, because method `duplicate` mentions `Self` outside of \
+            its receiver. Consider adding `where Self: Sized` to it",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn debug_emits_expansion_as_deprecation_note() {
+        // Given a trait
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn find(&self) -> Option<i32>;
+            }
+        });
+
+        // When expanded with `dummies(debug)`
+        let args = Args {
+            debug: true,
+            ..Args::default()
+        };
+        let output = expand(org_trait, &args).unwrap().to_string();
+
+        // Then a deprecated item mentioning expansion and chosen strategies is used
+        assert!(output.contains("# [deprecated (note ="));
+        assert!(output.contains("expansion of `dummies` for `MyTrait`"));
+        assert!(output.contains("find: Option { some: UnknownType(i32) } (Default)"));
+        assert!(output.contains("let _ = DummiesDebug"));
+    }
+
+    #[test]
+    fn unsafe_trait_requires_opt_in() {
+        // Given an unsafe trait
+        let org_trait = given(quote! {
+            unsafe trait RawDevice {}
+        });
+
+        // When expanded without and with `allow_unsafe`
+        let denied = expand(org_trait.clone(), &Args::default());
+        let args = Args {
+            allow_unsafe: true,
+            ..Args::default()
+        };
+        let allowed = expand(org_trait, &args).unwrap();
+
+        // Then only the explicit opt in implements the trait for the dummy
+        assert!(denied.is_err());
+        let expected = quote! {
+            unsafe trait RawDevice {}
+
+            unsafe impl RawDevice for double_trait::__private::Dummy {}
+        };
+        assert_eq!(expected.to_string(), allowed.to_string());
+    }
+
+    #[test]
+    fn dummy_returns_default_for_associated_types() {
+        // Given a trait with a method returning an associated type
+        let org_trait = given(quote! {
+            trait Parser {
+                type Output;
+                fn parse(&self, input: &str) -> Self::Output;
+            }
+        });
+
+        // When generating the dummy
+        let output = expand(org_trait, &Args::default()).unwrap();
+
+        // Then the trait default panics, but the dummy returns the default of the associated type
+        let expected = quote! {
+            trait Parser {
+                type Output;
+                fn parse(&self, _: &str) -> Self::Output {
+                    let double_trait_name = stringify!(Parser);
+                    let fn_name = stringify!(parse);
+                    double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                    unimplemented!("{double_trait_name}::{fn_name}")
+                }
+            }
+
+            impl Parser for double_trait::__private::Dummy {
+                type Output = double_trait::__private::Dummy;
+                fn parse(&self, _: &str) -> Self::Output {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(Parser);
+                        let fn_name = stringify!(parse);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    Default::default()
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+
+    #[test]
+    fn dummy_struct_returns_itself() {
+        // Given a trait with a constructor
+        let org_trait = given(quote! {
+            pub trait Connection {
+                fn open() -> Self where Self: Sized;
+            }
+        });
+
+        // When generating a dedicated dummy struct
+        let args = Args {
+            dummy: DummyArg::Struct(None),
+            ..Args::default()
+        };
+        let output = expand(org_trait, &args).unwrap().to_string();
+
+        // Then the struct implements the constructor using its `Default` implementation
+        let expected = quote! {
+            impl Connection for ConnectionDummy {
+                fn open() -> Self where Self: Sized {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(Connection);
+                        let fn_name = stringify!(open);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    Default::default()
+                }
+            }
+        };
+        assert!(output.ends_with(&expected.to_string()));
+    }
+
     fn given(item: proc_macro2::TokenStream) -> ItemTrait {
         let item: ItemTrait = parse2(item).unwrap();
         item
@@ -1,15 +1,33 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    Ident, ImplItem, ImplItemType, ItemTrait, Token, TraitItem, TypeModifiers, Visibility,
-    spanned::Spanned,
+    FnModifiers, GenericArgument, Ident, ImplItem, ImplItemFn, ImplItemType, ItemTrait, Lifetime,
+    PathArguments, Token, TraitBound, TraitItem, TraitItemFn, TraitItemType, Type, TypeModifiers,
+    TypeParamBound, Visibility, parse_quote, spanned::Spanned,
 };
 
-/// Implemntation of double trait for `Dummy` type.
-pub fn dummy_impl(double_trait_name: Ident, org_trait: ItemTrait) -> TokenStream {
-    let items = org_trait.items.into_iter().filter_map(transform_trait_item);
+/// Implemntation of double trait for `Dummy` type. `dummy` is usually `double_trait::Dummy`, but
+/// users may choose to supply their own type instead. `methods` override the default
+/// implementations of the trait.
+pub fn dummy_impl(
+    double_trait_name: Ident,
+    org_trait: ItemTrait,
+    dummy: &Type,
+    methods: Vec<TraitItemFn>,
+) -> TokenStream {
+    // Generic traits are implemented for every choice of generic arguments.
+    let (impl_generics, type_generics, where_clause) = org_trait.generics.split_for_impl();
+    let unsafety = org_trait.unsafety;
+    let impl_head = quote! {
+        #unsafety impl #impl_generics #double_trait_name #type_generics for #dummy #where_clause
+    };
+    let items = org_trait
+        .items
+        .into_iter()
+        .filter_map(|item| transform_trait_item(item, dummy))
+        .chain(methods.into_iter().filter_map(impl_fn));
     quote! {
-        impl #double_trait_name for double_trait::Dummy{
+        #impl_head {
             #(#items)*
         }
     }
@@ -17,9 +35,10 @@ pub fn dummy_impl(double_trait_name: Ident, org_trait: ItemTrait) -> TokenStream
 
 // We provide a dummy implementation for associated types. We do this in a dummy impl, because at
 // the time of writing this, default types in traits are not supported by stable Rust.
-fn transform_trait_item(item: TraitItem) -> Option<ImplItem> {
+fn transform_trait_item(item: TraitItem, dummy: &Type) -> Option<ImplItem> {
     if let TraitItem::Type(ty_item) = item {
         let span = ty_item.span();
+        let ty = associated_type(&ty_item, dummy);
         let impl_item = ImplItemType {
             attrs: ty_item.attrs,
             vis: Visibility::Inherited,
@@ -27,7 +46,7 @@ fn transform_trait_item(item: TraitItem) -> Option<ImplItem> {
             ident: ty_item.ident,
             generics: ty_item.generics,
             eq_token: Token![=](span),
-            ty: syn::parse_quote! { double_trait::Dummy },
+            ty,
             semi_token: Token![;](span),
             modifiers: TypeModifiers::default(),
         };
@@ -37,12 +56,123 @@ fn transform_trait_item(item: TraitItem) -> Option<ImplItem> {
     }
 }
 
+/// Turns a method with a default implementation into an item of the impl.
+fn impl_fn(fn_item: TraitItemFn) -> Option<ImplItem> {
+    Some(ImplItem::Fn(ImplItemFn {
+        attrs: fn_item.attrs,
+        vis: Visibility::Inherited,
+        modifiers: FnModifiers::default(),
+        sig: fn_item.sig,
+        block: fn_item.default?,
+    }))
+}
+
+/// Chooses a type for an associated type, which satisfies its bounds. Falls back to the dummy, for
+/// which we do not know any bounds.
+///
+/// A type is only chosen, if we know it to satisfy every single bound, e.g. `Iterator<Item = u8> +
+/// Display` falls back to the dummy, since `std::iter::Empty` does not implement `Display`.
+fn associated_type(ty_item: &TraitItemType, dummy: &Type) -> Type {
+    // References borrow for the first lifetime of a generic associated type, if any.
+    let lifetime = ty_item
+        .generics
+        .lifetimes()
+        .next()
+        .map(|param| param.lifetime.clone())
+        .unwrap_or_else(|| Lifetime::new("'static", ty_item.ident.span()));
+    let trait_bounds: Vec<_> = ty_item
+        .bounds
+        .iter()
+        .filter_map(|bound| match bound {
+            // `?Sized` is satisfied by any type
+            TypeParamBound::Trait(trait_bound) if trait_bound.maybe.is_none() => Some(trait_bound),
+            _ => None,
+        })
+        .collect();
+    let chosen = trait_bounds.iter().enumerate().find_map(|(index, bound)| {
+        let (ty, satisfies) = candidate(bound, &lifetime)?;
+        let satisfies_all = trait_bounds.iter().enumerate().all(|(other, bound)| {
+            let Some(last) = bound.path.segments.last() else {
+                return false;
+            };
+            other == index
+                || (last.arguments.is_empty() && satisfies.iter().any(|name| last.ident == name))
+        });
+        satisfies_all.then_some(ty)
+    });
+    chosen.unwrap_or_else(|| dummy.clone())
+}
+
+/// A type satisfying `bound`, together with the traits without generic arguments it is known to
+/// implement in addition.
+fn candidate(bound: &TraitBound, lifetime: &Lifetime) -> Option<(Type, &'static [&'static str])> {
+    let last = bound.path.segments.last()?;
+    let args = match &last.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+        _ => Vec::new(),
+    };
+    if last.ident == "AsRef" {
+        // E.g. `AsRef<[u8]>` or `AsRef<str>`
+        let Some(GenericArgument::Type(target)) = args.first() else {
+            return None;
+        };
+        // Whether the reference is e.g. `Send` or `Debug` depends on the target
+        Some((
+            parse_quote!(&#lifetime #target),
+            &["Clone", "Copy", "Sized", "Unpin"],
+        ))
+    } else if last.ident == "Iterator" {
+        let item = args.iter().find_map(|arg| match arg {
+            GenericArgument::AssocType(assoc) if assoc.ident == "Item" => Some(&assoc.ty),
+            _ => None,
+        })?;
+        Some((
+            parse_quote!(std::iter::Empty<#item>),
+            &[
+                "Clone",
+                "Debug",
+                "Default",
+                "DoubleEndedIterator",
+                "ExactSizeIterator",
+                "FusedIterator",
+                "Send",
+                "Sized",
+                "Sync",
+                "Unpin",
+            ],
+        ))
+    } else if last.ident == "Display" {
+        Some((
+            parse_quote!(&#lifetime str),
+            &[
+                "Clone",
+                "Copy",
+                "Debug",
+                "Default",
+                "Eq",
+                "Hash",
+                "Ord",
+                "PartialEq",
+                "PartialOrd",
+                "Send",
+                "Sized",
+                "Sync",
+                "ToString",
+                "Unpin",
+            ],
+        ))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quote::quote;
     use syn::{Ident, ItemTrait, parse2};
 
     use super::dummy_impl;
+    use crate::args::Args;
 
     #[test]
     fn provide_default_implementation_for_associated_types() {
@@ -57,13 +187,121 @@ mod tests {
         );
 
         // When generating the dummy implementation
-        let dummy_impl = dummy_impl(double_trait_name, org_trait);
+        let dummy = Args::default().dummy_type(&double_trait_name).unwrap();
+        let dummy_impl = dummy_impl(double_trait_name, org_trait, &dummy, Vec::new());
 
         // Then the dummy implementation should provide a default type for the associated type
         let actual = quote! { #dummy_impl };
         let expected = quote! {
-            impl DoubleTrait for double_trait::Dummy {
-                type AssociatedType = double_trait::Dummy;
+            impl DoubleTrait for double_trait::__private::Dummy {
+                type AssociatedType = double_trait::__private::Dummy;
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn implement_generic_trait_for_all_generic_arguments() {
+        // Given a generic trait
+        let (double_trait_name, org_trait) = given(
+            quote! { MyTrait },
+            quote! {
+                trait MyTrait<T: Clone> where T: Send {}
+            },
+        );
+
+        // When generating the dummy implementation
+        let dummy = Args::default().dummy_type(&double_trait_name).unwrap();
+        let dummy_impl = dummy_impl(double_trait_name, org_trait, &dummy, Vec::new());
+
+        // Then the trait is implemented for all generic arguments
+        let actual = quote! { #dummy_impl };
+        let expected = quote! {
+            impl<T: Clone> MyTrait<T> for double_trait::__private::Dummy where T: Send {}
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn unsafe_impl_for_unsafe_trait() {
+        // Given an unsafe trait
+        let (double_trait_name, org_trait) = given(
+            quote! { RawDevice },
+            quote! {
+                unsafe trait RawDevice {}
+            },
+        );
+
+        // When generating the dummy implementation
+        let dummy = Args::default().dummy_type(&double_trait_name).unwrap();
+        let dummy_impl = dummy_impl(double_trait_name, org_trait, &dummy, Vec::new());
+
+        // Then the implementation is unsafe, too
+        let actual = quote! { #dummy_impl };
+        let expected = quote! {
+            unsafe impl RawDevice for double_trait::__private::Dummy {}
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn generic_associated_types_satisfy_bounds() {
+        // Given a trait with generic associated types with bounds and where clauses
+        let (double_trait_name, org_trait) = given(
+            quote! { Reader },
+            quote! {
+                trait Reader {
+                    type Buf<'a>: AsRef<[u8]> where Self: 'a;
+                    type Bytes<'a>: Iterator<Item = &'a u8> where Self: 'a;
+                    type Name: std::fmt::Display;
+                }
+            },
+        );
+
+        // When generating the dummy implementation
+        let dummy = Args::default().dummy_type(&double_trait_name).unwrap();
+        let dummy_impl = dummy_impl(double_trait_name, org_trait, &dummy, Vec::new());
+
+        // Then the types satisfy the bounds and the where clauses are kept
+        let actual = quote! { #dummy_impl };
+        let expected = quote! {
+            impl Reader for double_trait::__private::Dummy {
+                type Buf<'a> = &'a [u8] where Self: 'a;
+                type Bytes<'a> = std::iter::Empty<&'a u8> where Self: 'a;
+                type Name = &'static str;
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn associated_types_satisfy_all_bounds_or_fall_back_to_dummy() {
+        // Given associated types with combined bounds, of which only some are satisfied by the
+        // type chosen for the first bound
+        let (double_trait_name, org_trait) = given(
+            quote! { Reader },
+            quote! {
+                trait Reader {
+                    type It: Iterator<Item = u8> + std::fmt::Display;
+                    type Name: std::fmt::Display + Clone + Send;
+                    type Bytes: AsRef<[u8]> + Send;
+                    type Items: Iterator<Item = u8> + ExactSizeIterator + ?Sized;
+                }
+            },
+        );
+
+        // When generating the dummy implementation
+        let dummy = Args::default().dummy_type(&double_trait_name).unwrap();
+        let dummy_impl = dummy_impl(double_trait_name, org_trait, &dummy, Vec::new());
+
+        // Then a type is only chosen if it is known to satisfy every bound
+        let actual = quote! { #dummy_impl };
+        let expected = quote! {
+            impl Reader for double_trait::__private::Dummy {
+                type It = double_trait::__private::Dummy;
+                type Name = &'static str;
+                type Bytes = double_trait::__private::Dummy;
+                type Items = std::iter::Empty<u8>;
             }
         };
         assert_eq!(actual.to_string(), expected.to_string());
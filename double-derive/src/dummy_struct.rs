@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, ItemTrait};
+
+use crate::dyn_compatibility::check_dyn_compatible;
+
+/// A unit struct dedicated to a single trait. In contrast to the shared `double_trait::Dummy` it
+/// can be told apart in debug output. The implementation of the trait itself is generated by
+/// [`crate::dummy_impl::dummy_impl`].
+pub fn dummy_struct(name: &Ident, org_trait: &ItemTrait) -> TokenStream {
+    let vis = &org_trait.vis;
+    let trait_name = &org_trait.ident;
+    // Allow for passing the dummy where a boxed trait object is expected, if the trait allows for
+    // it.
+    let into_box = check_dyn_compatible(org_trait).is_ok().then(|| {
+        quote! {
+            impl From<#name> for Box<dyn #trait_name> {
+                fn from(dummy: #name) -> Self {
+                    Box::new(dummy)
+                }
+            }
+        }
+    });
+    quote! {
+        #[derive(Debug, Default, Clone)]
+        #vis struct #name;
+
+        #into_box
+    }
+}
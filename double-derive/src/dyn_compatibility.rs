@@ -0,0 +1,276 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{
+    Error, FnArg, GenericParam, Ident, ItemTrait, Path, ReturnType, TraitItem, TraitItemFn, Type,
+    TypeParamBound, WhereClause, WherePredicate,
+};
+
+/// Supertraits from `std` which would render a trait dyn incompatible, because they require `Self`
+/// to be `Sized` or mention `Self` in their methods.
+const DYN_INCOMPATIBLE_SUPERTRAITS: &[&str] = &[
+    "Sized",
+    "Clone",
+    "Copy",
+    "Default",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+];
+
+/// Checks whether `dyn Trait` is a valid type, so we can generate helpers converting dummies into
+/// trait objects.
+///
+/// This is a syntactic approximation of the rules the compiler applies. We can not see the
+/// definitions of supertraits, so we only reject the ones from `std` we know about. Traits with
+/// generic parameters or associated types are rejected as well, since we could not name the trait
+/// object type without specifying them.
+///
+/// In case the trait is not dyn compatible, the error points to the offending item.
+pub fn check_dyn_compatible(org_trait: &ItemTrait) -> syn::Result<()> {
+    if let Some(param) = org_trait.generics.params.first() {
+        return Err(Error::new_spanned(
+            param,
+            "trait objects of generic traits are not supported by double-trait",
+        ));
+    }
+    if let Some(where_clause) = &org_trait.generics.where_clause
+        && requires_sized_self(where_clause)
+    {
+        return Err(Error::new_spanned(
+            where_clause,
+            "trait is not dyn compatible, because it requires `Self: Sized`",
+        ));
+    }
+    for supertrait in &org_trait.supertraits {
+        let TypeParamBound::Trait(bound) = supertrait else {
+            continue;
+        };
+        let Some(last) = bound.path.segments.last() else {
+            continue;
+        };
+        if DYN_INCOMPATIBLE_SUPERTRAITS.contains(&last.ident.to_string().as_str()) {
+            return Err(Error::new_spanned(
+                supertrait,
+                format!(
+                    "trait is not dyn compatible, because it requires `{}`",
+                    last.ident
+                ),
+            ));
+        }
+    }
+    for item in &org_trait.items {
+        match item {
+            TraitItem::Const(item) => {
+                return Err(Error::new_spanned(
+                    item,
+                    "trait is not dyn compatible, because it has an associated constant",
+                ));
+            }
+            TraitItem::Type(item) => {
+                return Err(Error::new_spanned(
+                    item,
+                    "trait objects of traits with associated types are not supported by \
+                    double-trait",
+                ));
+            }
+            TraitItem::Fn(fn_item) => check_method(fn_item)?,
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Implements `IntoDyn<dyn Trait>` for the dummy type. If the dummy is known to be thread safe,
+/// `IntoDyn<dyn Trait + Send + Sync>` is implemented as well.
+pub fn into_dyn_impl(
+    trait_name: &Ident,
+    dummy: &Type,
+    krate: &Path,
+    thread_safe: bool,
+) -> TokenStream {
+    let mut trait_objects = vec![quote! { dyn #trait_name }];
+    if thread_safe {
+        trait_objects.push(quote! { dyn #trait_name + Send + Sync });
+    }
+    quote! {
+        #(
+            impl #krate::__private::IntoDyn<#trait_objects> for #dummy {
+                fn into_boxed(self) -> Box<#trait_objects> {
+                    Box::new(self)
+                }
+
+                fn into_arc(self) -> std::sync::Arc<#trait_objects> {
+                    std::sync::Arc::new(self)
+                }
+
+                fn into_rc(self) -> std::rc::Rc<#trait_objects> {
+                    std::rc::Rc::new(self)
+                }
+            }
+        )*
+    }
+}
+
+fn check_method(fn_item: &TraitItemFn) -> syn::Result<()> {
+    // Methods which require `Self: Sized` are not available on trait objects, so they can not
+    // render the trait dyn incompatible.
+    if let Some(where_clause) = &fn_item.sig.generics.where_clause
+        && requires_sized_self(where_clause)
+    {
+        return Ok(());
+    }
+    let sig = &fn_item.sig;
+    let error = |msg: &str| {
+        Err(Error::new_spanned(
+            sig,
+            format!(
+                "trait is not dyn compatible, because method `{}` {msg}. Consider adding \
+                `where Self: Sized` to it",
+                sig.ident
+            ),
+        ))
+    };
+    if sig.receiver().is_none() {
+        return error("has no receiver");
+    }
+    if sig.asyncness.is_some() {
+        return error("is async");
+    }
+    if sig
+        .generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Lifetime(_)))
+    {
+        return error("has generic type parameters");
+    }
+    let argument_types = sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(pat_type) => Some(pat_type.ty.to_token_stream()),
+        FnArg::Receiver(_) => None,
+    });
+    let return_type = match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(ty.to_token_stream()),
+    };
+    for tokens in argument_types.chain(return_type) {
+        if contains_ident(tokens.clone(), "impl") {
+            return error("uses `impl Trait`");
+        }
+        if mentions_self_type(tokens) {
+            return error("mentions `Self` outside of its receiver");
+        }
+    }
+    Ok(())
+}
+
+/// `true` if the where clause contains `Self: Sized`.
+fn requires_sized_self(where_clause: &WhereClause) -> bool {
+    where_clause.predicates.iter().any(|predicate| {
+        let WherePredicate::Type(predicate) = predicate else {
+            return false;
+        };
+        let bounded_ty = predicate.bounded_ty.to_token_stream().to_string();
+        bounded_ty == "Self"
+            && predicate.bounds.iter().any(|bound| {
+                matches!(bound, TypeParamBound::Trait(bound) if bound.path.is_ident("Sized"))
+            })
+    })
+}
+
+fn contains_ident(tokens: TokenStream, ident: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(i) => i == ident,
+        TokenTree::Group(group) => contains_ident(group.stream(), ident),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
+}
+
+/// `true` if the tokens mention `Self` itself. Associated types like `Self::Item` are fine, though.
+fn mentions_self_type(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "Self" => {
+                let is_path =
+                    matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ':');
+                if !is_path {
+                    return true;
+                }
+            }
+            TokenTree::Group(group) if mentions_self_type(group.stream()) => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{ItemTrait, parse2};
+
+    use super::check_dyn_compatible;
+
+    #[test]
+    fn trait_with_reference_receivers_is_dyn_compatible() {
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn answer(&self) -> i32;
+                fn update(&mut self, value: &Self::Foo);
+            }
+        });
+
+        assert!(check_dyn_compatible(&org_trait).is_ok());
+    }
+
+    #[test]
+    fn method_returning_self_is_not_dyn_compatible() {
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn duplicate(&self) -> Self;
+            }
+        });
+
+        let error = check_dyn_compatible(&org_trait).unwrap_err();
+
+        assert_eq!(
+            "trait is not dyn compatible, because method `duplicate` mentions `Self` outside of \
+            its receiver. Consider adding `where Self: Sized` to it",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn methods_requiring_sized_self_are_ignored() {
+        let org_trait = given(quote! {
+            trait MyTrait {
+                fn new() -> Self where Self: Sized;
+                fn generic<T>(&self, value: T) where Self: Sized;
+            }
+        });
+
+        assert!(check_dyn_compatible(&org_trait).is_ok());
+    }
+
+    #[test]
+    fn trait_requiring_sized_self_is_not_dyn_compatible() {
+        let org_trait = given(quote! {
+            trait MyTrait where Self: Sized {
+                fn answer(&self) -> i32;
+            }
+        });
+
+        let error = check_dyn_compatible(&org_trait).unwrap_err();
+
+        assert_eq!(
+            "trait is not dyn compatible, because it requires `Self: Sized`",
+            error.to_string()
+        );
+    }
+
+    fn given(item: proc_macro2::TokenStream) -> ItemTrait {
+        parse2(item).unwrap()
+    }
+}
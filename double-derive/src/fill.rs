@@ -0,0 +1,440 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Expr, ExprPath, FnModifiers, GenericArgument, GenericParam, Ident, ImplItem, ImplItemFn,
+    ItemImpl, ItemTrait, Lifetime, Path, PathArguments, TraitItem, Type, TypePath, Visibility,
+    bracketed,
+    parse::{Parse, ParseStream, Parser},
+    parse_quote, parse2,
+    visit_mut::{self, VisitMut},
+};
+
+use crate::{
+    args::{Args, MethodArgs},
+    double_trait::double_trait,
+};
+
+/// A hidden `macro_rules!` macro, sharing its name with the trait, which describes the trait to
+/// [`crate::fill`]. A proc macro on an impl can not see the trait, but it can invoke this macro
+/// with the impl, which then forwards both the trait and the impl to [`crate::fill_impl`].
+pub fn fill_macro(org_trait: &ItemTrait, args: &Args) -> TokenStream {
+    let trait_name = &org_trait.ident;
+    let macro_name = format_ident!("__double_trait_fill_{trait_name}");
+    let krate = &args.krate;
+    let body_args = args.body_args();
+    // `macro_rules!` can not be exported beyond the crate without polluting its root.
+    let vis = match &org_trait.vis {
+        Visibility::Public(_) => quote! { pub(crate) },
+        vis => quote! { #vis },
+    };
+    quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_name {
+            ($($item_impl:tt)*) => {
+                #krate::__private::fill_impl! {
+                    [#body_args]
+                    #org_trait
+                    $($item_impl)*
+                }
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #macro_name as #trait_name;
+    }
+}
+
+/// Removes the `#[dummies(...)]` attributes from the methods of the trait, without adding any
+/// default implementations.
+pub fn strip_method_args(mut org_trait: ItemTrait) -> syn::Result<ItemTrait> {
+    for item in &mut org_trait.items {
+        if let TraitItem::Fn(fn_item) = item {
+            MethodArgs::extract(&mut fn_item.attrs)?;
+        }
+    }
+    Ok(org_trait)
+}
+
+/// Input of [`crate::fill_impl`], e.g. `[strict] trait MyTrait { ... } impl MyTrait for Stub {
+/// ... }`.
+pub struct FillInput {
+    args: Args,
+    org_trait: ItemTrait,
+    item_impl: ItemImpl,
+}
+
+impl Parse for FillInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let mut args = Args::default();
+        syn::meta::parser(|meta| args.parse(meta)).parse2(content.parse()?)?;
+        Ok(FillInput {
+            args,
+            org_trait: input.parse()?,
+            item_impl: input.parse()?,
+        })
+    }
+}
+
+/// The main implementation of [`crate::fill_impl`].
+pub fn expand_fill_impl(input: FillInput) -> syn::Result<TokenStream> {
+    let FillInput {
+        args,
+        org_trait,
+        item_impl,
+    } = input;
+    let item_impl = fill(org_trait, item_impl, &args)?;
+    Ok(quote! { #item_impl })
+}
+
+/// Adds the generated default implementations of all methods the impl does not implement itself.
+pub fn fill(org_trait: ItemTrait, mut item_impl: ItemImpl, args: &Args) -> syn::Result<ItemImpl> {
+    let implemented: Vec<Ident> = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(fn_item) => Some(fn_item.sig.ident.clone()),
+            _ => None,
+        })
+        .collect();
+    // The methods of generic traits mention the generic parameters of the trait, which are unknown
+    // within the impl.
+    let mut generic_arguments = GenericArguments::of_impl(&org_trait, &item_impl)?;
+    let (double_trait, _defaults) = double_trait(org_trait.clone(), args)?;
+    for (org_item, item) in org_trait.items.into_iter().zip(double_trait.items) {
+        // Methods with a default implementation in the trait itself need not be filled.
+        let (TraitItem::Fn(org_fn), TraitItem::Fn(fn_item)) = (org_item, item) else {
+            continue;
+        };
+        if org_fn.default.is_some() || implemented.contains(&fn_item.sig.ident) {
+            continue;
+        }
+        let Some(mut block) = fn_item.default else {
+            continue;
+        };
+        let mut sig = fn_item.sig;
+        generic_arguments.visit_signature_mut(&mut sig);
+        generic_arguments.visit_block_mut(&mut block);
+        item_impl.items.push(ImplItem::Fn(ImplItemFn {
+            attrs: fn_item.attrs,
+            vis: Visibility::Inherited,
+            modifiers: FnModifiers::default(),
+            sig,
+            block,
+        }));
+    }
+    Ok(item_impl)
+}
+
+/// Generic arguments an impl passes to the generic parameters of the trait. Substitutes the
+/// parameters with the arguments, when visiting the methods copied from the trait.
+#[derive(Default)]
+struct GenericArguments {
+    lifetimes: Vec<(Ident, Lifetime)>,
+    types: Vec<(Ident, Type)>,
+    consts: Vec<(Ident, Expr)>,
+}
+
+impl GenericArguments {
+    fn of_impl(org_trait: &ItemTrait, item_impl: &ItemImpl) -> syn::Result<Self> {
+        let mut substitution = GenericArguments::default();
+        let Some((trait_path, _for)) = &item_impl.trait_ else {
+            return Ok(substitution);
+        };
+        let arguments = match trait_path.segments.last().map(|last| &last.arguments) {
+            Some(PathArguments::AngleBracketed(arguments)) => arguments.args.iter().collect(),
+            _ => Vec::new(),
+        };
+        let mut lifetimes = arguments.iter().filter_map(|arg| match arg {
+            GenericArgument::Lifetime(lifetime) => Some(lifetime),
+            _ => None,
+        });
+        let mut others = arguments
+            .iter()
+            .filter(|arg| matches!(arg, GenericArgument::Type(_) | GenericArgument::Const(_)));
+        for param in &org_trait.generics.params {
+            let missing = |ident: &Ident| {
+                syn::Error::new_spanned(
+                    trait_path,
+                    format!("expected a generic argument for `{ident}` of the trait"),
+                )
+            };
+            match param {
+                GenericParam::Lifetime(param) => {
+                    let ident = &param.lifetime.ident;
+                    let lifetime = lifetimes.next().ok_or_else(|| missing(ident))?;
+                    substitution
+                        .lifetimes
+                        .push((ident.clone(), lifetime.clone()));
+                }
+                GenericParam::Type(param) => {
+                    let mut ty = match (others.next(), &param.default) {
+                        (Some(GenericArgument::Type(ty)), _) => ty.clone(),
+                        (None, Some((_eq, default))) => default.clone(),
+                        _ => return Err(missing(&param.ident)),
+                    };
+                    // Defaults may refer to preceding parameters, e.g. `trait Get<K, V = K>`.
+                    substitution.visit_type_mut(&mut ty);
+                    substitution.types.push((param.ident.clone(), ty));
+                }
+                GenericParam::Const(param) => {
+                    let expr = match (others.next(), &param.default) {
+                        (Some(GenericArgument::Const(expr)), _) => expr.clone(),
+                        // A const argument given by name is parsed as a type, e.g. `Buffer<N>`
+                        (Some(GenericArgument::Type(ty)), _) => parse2(quote! { #ty })?,
+                        (None, Some((_eq, default))) => default.clone(),
+                        _ => return Err(missing(&param.ident)),
+                    };
+                    substitution.consts.push((param.ident.clone(), expr));
+                }
+            }
+        }
+        Ok(substitution)
+    }
+
+    fn lookup<'a, T>(params: &'a [(Ident, T)], ident: &Ident) -> Option<&'a T> {
+        params
+            .iter()
+            .find(|(param, _)| param == ident)
+            .map(|(_, argument)| argument)
+    }
+
+    /// The generic parameter of the trait a path starts with, e.g. `T` in `T::Item`.
+    fn type_param_of<'a>(&'a self, path: &Path) -> Option<&'a Type> {
+        let first = path.segments.first()?;
+        if path.leading_colon.is_some() || !first.arguments.is_empty() {
+            return None;
+        }
+        Self::lookup(&self.types, &first.ident)
+    }
+
+    /// The name of a const parameter of the trait, if `path` consists of nothing else.
+    fn const_param_of<'a>(&'a self, path: &Path) -> Option<&'a Expr> {
+        let ident = path.get_ident()?;
+        Self::lookup(&self.consts, ident)
+    }
+}
+
+impl VisitMut for GenericArguments {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath {
+            qself: None, path, ..
+        }) = ty
+            && let Some(argument) = self.type_param_of(path)
+        {
+            let rest = path.segments.iter().skip(1);
+            *ty = if path.segments.len() == 1 {
+                argument.clone()
+            } else {
+                parse_quote! { <#argument> #(::#rest)* }
+            };
+            return;
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Path(ExprPath {
+            qself: None, path, ..
+        }) = expr
+        {
+            if let Some(argument) = self.const_param_of(path) {
+                *expr = argument.clone();
+                return;
+            }
+            if path.segments.len() > 1
+                && let Some(argument) = self.type_param_of(path)
+            {
+                // E.g. `T::default()`
+                let rest = path.segments.iter().skip(1);
+                *expr = parse_quote! { <#argument> #(::#rest)* };
+                return;
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_generic_argument_mut(&mut self, argument: &mut GenericArgument) {
+        // Const parameters passed on by name are parsed as types, e.g. `[u8; N]` in `Buffer<N>`
+        if let GenericArgument::Type(Type::Path(TypePath {
+            qself: None, path, ..
+        })) = argument
+            && let Some(expr) = self.const_param_of(path)
+        {
+            *argument = GenericArgument::Const(expr.clone());
+            return;
+        }
+        visit_mut::visit_generic_argument_mut(self, argument);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if let Some(argument) = Self::lookup(&self.lifetimes, &lifetime.ident) {
+            *lifetime = argument.clone();
+        }
+    }
+}
+
+/// The main implementation of [`crate::fill`]. Invokes the macro generated by `dummies(fill)`
+/// alongside the trait with the annotated impl.
+pub fn expand_fill_attribute(item_impl: ItemImpl) -> syn::Result<TokenStream> {
+    let Some((trait_path, _for)) = &item_impl.trait_ else {
+        return Err(syn::Error::new_spanned(
+            item_impl.self_ty,
+            "`fill` must be applied to an implementation of a trait",
+        ));
+    };
+    // The macro shares the path of the trait, but does not accept generic arguments.
+    let mut macro_path: Path = trait_path.clone();
+    if let Some(last) = macro_path.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+    Ok(quote! {
+        #macro_path! { #item_impl }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{ItemImpl, ItemTrait, parse2};
+
+    use super::{expand_fill_attribute, fill};
+    use crate::args::Args;
+
+    #[test]
+    fn fill_missing_methods() {
+        // Given a trait with two methods and an impl implementing only one of them
+        let org_trait: ItemTrait = parse2(quote! {
+            trait MyTrait {
+                fn answer(&self) -> i32;
+                fn find(&self) -> Option<i32>;
+            }
+        })
+        .unwrap();
+        let item_impl: ItemImpl = parse2(quote! {
+            impl MyTrait for Stub {
+                fn answer(&self) -> i32 {
+                    42
+                }
+            }
+        })
+        .unwrap();
+
+        // When filling the impl
+        let item_impl = fill(org_trait, item_impl, &Args::default()).unwrap();
+
+        // Then the missing method is added
+        let actual = quote! { #item_impl };
+        let expected = quote! {
+            impl MyTrait for Stub {
+                fn answer(&self) -> i32 {
+                    42
+                }
+                fn find(&self) -> Option<i32> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(MyTrait);
+                        let fn_name = stringify!(find);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    None
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn fill_substitutes_generic_arguments_of_impl() {
+        // Given a generic trait and an impl passing arguments for some of its parameters
+        let org_trait: ItemTrait = parse2(quote! {
+            trait Lookup<'a, K, V = Vec<K>, const N: usize = 2> {
+                fn find(&self, key: &'a K) -> Option<V>;
+                fn keys(&self) -> Option<[K; N]>;
+            }
+        })
+        .unwrap();
+        let item_impl: ItemImpl = parse2(quote! {
+            impl Lookup<'static, u32, String> for Stub {}
+        })
+        .unwrap();
+
+        // When filling the impl
+        let item_impl = fill(org_trait, item_impl, &Args::default()).unwrap();
+
+        // Then the parameters of the trait are replaced by the arguments of the impl or their
+        // defaults
+        let actual = quote! { #item_impl };
+        let expected = quote! {
+            impl Lookup<'static, u32, String> for Stub {
+                fn find(&self, _: &'static u32) -> Option<String> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(Lookup);
+                        let fn_name = stringify!(find);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    None
+                }
+                fn keys(&self) -> Option<[u32; 2]> {
+                    if double_trait::__private::is_strict_mode() {
+                        let double_trait_name = stringify!(Lookup);
+                        let fn_name = stringify!(keys);
+                        double_trait::__private::report_unimplemented(double_trait_name, fn_name);
+                        unimplemented!("{double_trait_name}::{fn_name}")
+                    }
+                    None
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn fill_generic_trait_without_arguments_is_an_error() {
+        // Given a generic trait and an impl omitting the arguments
+        let org_trait: ItemTrait = parse2(quote! {
+            trait Get<T> {
+                fn get(&self) -> Option<T>;
+            }
+        })
+        .unwrap();
+        let item_impl: ItemImpl = parse2(quote! {
+            impl Get for Stub {}
+        })
+        .unwrap();
+
+        // When filling the impl
+        let result = fill(org_trait, item_impl, &Args::default());
+
+        // Then the missing argument is reported
+        let Err(error) = result else {
+            panic!("Expected an error");
+        };
+        assert_eq!(
+            "expected a generic argument for `T` of the trait",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn fill_attribute_invokes_macro_named_after_trait() {
+        // Given an impl of a generic trait
+        let item_impl: ItemImpl = parse2(quote! {
+            impl my_mod::MyTrait<i32> for Stub {}
+        })
+        .unwrap();
+
+        // When expanding `fill`
+        let output = expand_fill_attribute(item_impl).unwrap();
+
+        // Then the macro is invoked without generic arguments
+        let expected = quote! {
+            my_mod::MyTrait! { impl my_mod::MyTrait<i32> for Stub {} }
+        };
+        assert_eq!(expected.to_string(), output.to_string());
+    }
+}
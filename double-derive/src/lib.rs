@@ -1,8 +1,15 @@
+mod args;
 mod double_trait;
 mod dummies;
 mod dummy_impl;
+mod dummy_struct;
+mod dyn_compatibility;
+mod fill;
+mod remote;
 
-use syn::{Error, ItemTrait, parse_macro_input};
+use syn::{Error, ItemImpl, ItemTrait, parse_macro_input};
+
+use crate::{args::Args, fill::FillInput, remote::RemoteTrait};
 
 /// Generates a "dummy" implementation for each method in a trait and implements the trait for `Dummy`.
 ///
@@ -10,7 +17,9 @@ use syn::{Error, ItemTrait, parse_macro_input};
 /// a trait. The compiler is happy as there is a default implementation and you can focus on
 /// overwriting the behavior which is of interest to your test.
 ///
-/// * Most default implementations will call `unimplemented!`.
+/// * Most default implementations will call `unimplemented!`. Before doing so they register the
+///   call, so it can be detected even if the panic is caught. See
+///   `double_trait::UnimplementedCallsGuard`.
 /// * Existing default implementations are respected and not overridden.
 /// * Methods returning `impl` Trait will not work unless they are specifically supproted by this
 ///   crate. One way to deal with this, is to give them an explicit default implementation in the
@@ -36,7 +45,9 @@ use syn::{Error, ItemTrait, parse_macro_input};
 ///   }
 ///   ```
 ///
-/// * Associated types are implemented using `Dummy`.
+/// * Associated types are implemented using `Dummy`, unless their bounds demand otherwise. E.g.
+///   `AsRef<[u8]>` is implemented using `&[u8]`, `Iterator<Item = T>` using `std::iter::Empty<T>`
+///   and `Display` using `&str`. Where clauses of generic associated types are kept.
 /// * Async methods and methods returning `impl Future` are supported and inherit the default from
 ///   their sync counterparts.
 /// * Methods returning `impl Iterator` are supported and will return an empty iterator.
@@ -44,17 +55,312 @@ use syn::{Error, ItemTrait, parse_macro_input};
 ///   return an empty Stream.
 /// * Methods returning `Result`, will use the default behavior of the `Ok` type and wrap it in
 ///   `Ok`.
-/// * Methods returning `Option` will return `None`.
+/// * Methods returning `Option` will return `None`. This includes options of supported `impl`
+///   Traits, e.g. `Option<impl Iterator<Item = T>>`.
 /// * Methods returning `Vec` will return `Vec::new`.
+/// * Methods returning `Poll` will return `Poll::Pending`, methods returning `ControlFlow` will
+///   continue with the default behavior of the `Continue` type and methods returning
+///   `cmp::Ordering` or `std::cmp::Ordering` will return `Ordering::Equal`. A bare `Ordering` is
+///   not recognized, since it may also be `std::sync::atomic::Ordering`.
+/// * Methods returning `Box`, `Rc` or `Arc`, will wrap the default behavior of the pointee.
+///   Methods returning `Cow` will borrow an empty `str` or slice, or own the default behavior of
+///   any other type.
+/// * Methods returning `&str` will return `""`, methods returning `&[T]` or `&mut [T]` an empty
+///   slice.
+/// * Methods returning any other reference, e.g. `&Config`, panic by default, since there is
+///   nothing to borrow from. The implementation for `Dummy` (or a dedicated dummy struct) instead
+///   leaks `Default::default()` of the referenced type, if it implements `Default`.
+/// * Methods returning `Self` or an associated type of `Self`, e.g. `Self::Output`, panic by
+///   default, since the trait knows nothing about the type. The implementation for `Dummy` (or a
+///   dedicated dummy struct) knows the type and returns `Default::default()` instead. This also
+///   applies to e.g. `Box<Self>` or `Result<Self, E>`.
+///
+/// # Arguments
+///
+/// * `crate = path`: Path to the `double-trait` crate used by the generated code. Defaults to
+///   `double_trait`. Useful if the crate has been renamed in `Cargo.toml` or is reexported by
+///   another crate. E.g.,
+///
+///   ```
+///   # mod test_support { pub use double_trait; }
+///   #[test_support::double_trait::dummies(crate = test_support::double_trait)]
+///   trait MyTrait {
+///       fn answer(&self) -> i32;
+///   }
+///   ```
+///
+/// * `no_dummy`: Do not implement the trait for `Dummy`. Useful if `Dummy` can not satisfy the
+///   bounds of the trait, or if you want to provide your own dummy implementation.
+/// * `dummy = MyDummy`: Implement the trait for `MyDummy` instead of `Dummy`. Associated types are
+///   set to `MyDummy`, too.
+///
+///   ```
+///   #[derive(Clone)]
+///   struct MyDummy;
+///
+///   #[double_trait::dummies(dummy = MyDummy)]
+///   trait MyTrait: Clone {
+///       fn answer(&self) -> i32;
+///   }
+///   ```
+///
+/// * `dummy_struct` or `dummy_struct = MyDummy`: Generate a unit struct dedicated to this trait and
+///   implement the trait for it, instead of `Dummy`. The struct derives `Debug`, `Default` and
+///   `Clone` and has the same visibility as the trait. Its name defaults to the name of the trait
+///   with a `Dummy` suffix. If the trait is dyn compatible, the struct can also be converted into a
+///   `Box<dyn MyTrait>`.
+///
+///   ```
+///   #[double_trait::dummies(dummy_struct)]
+///   trait MyTrait {
+///       fn answer(&self) -> i32;
+///   }
+///
+///   let boxed: Box<dyn MyTrait> = MyTraitDummy.into();
+///   ```
+///
+/// * `dyn_compatible`: Implement `IntoDyn<dyn MyTrait>` for the dummy, so it can be converted into
+///   `Box<dyn MyTrait>`, `Arc<dyn MyTrait>` or `Rc<dyn MyTrait>`, e.g. using `Dummy::boxed`.
+///   Emits a compile error pointing to the offending item, if the trait is not dyn compatible.
+///   Methods with a `where Self: Sized` clause do not affect dyn compatibility.
+/// * `verbose`: Panic messages of default implementations mention the arguments the method has been
+///   invoked with, as well as the location of the caller. Arguments which do not implement `Debug`
+///   are shown as `_`. The caller location is not available for `async fn`.
+///
+///   ```should_panic
+///   #[double_trait::dummies(verbose)]
+///   trait MyTrait {
+///       fn answer(&self, question: &str) -> i32;
+///   }
+///
+///   // Panics with: not implemented: MyTrait::answer(question: "Why?") called at src/main.rs:8:1
+///   double_trait::Dummy.answer("Why?");
+///   ```
+///
+/// * `on_unimplemented = todo!`: Macro invoked by default implementations which can not provide a
+///   sensible value. It is invoked with a format string, like `unimplemented!`, which is the
+///   default. Any macro with the same calling convention, e.g. `todo!` or `panic!` works, as long
+///   as it does not return.
+/// * `on_unimplemented = my_handler`: Like above, but invokes a function with the formatted message
+///   instead. The function must be able to return any type, e.g.
+///   `fn my_handler<T>(message: &str) -> T` or `fn my_handler(message: &str) -> !`. In strict mode
+///   the default implementation panics, should the function return nonetheless.
+///
+///   ```should_panic
+///   fn fail_test<T>(message: &str) -> T {
+///       panic!("Test double has been called unexpectedly: {message}")
+///   }
+///
+///   #[double_trait::dummies(on_unimplemented = fail_test)]
+///   trait MyTrait {
+///       fn answer(&self) -> i32;
+///   }
+///
+///   double_trait::Dummy.answer();
+///   ```
+///
+/// * `strict`: Every default implementation panics, even the ones which could return a sensible
+///   value like `()`, `None`, `Vec::new()` or `Ok(())`. This turns `Dummy` into a dummy which must
+///   not be called at all. Individual methods may override this using `#[dummies(strict)]` or
+///   `#[dummies(strict = false)]`. Strict mode can also be enabled at runtime for a single test
+///   using `double_trait::strict_mode`.
+///
+///   ```should_panic
+///   #[double_trait::dummies(strict)]
+///   trait MyTrait {
+///       fn notify(&self);
+///
+///       #[dummies(strict = false)]
+///       fn find(&self) -> Option<i32>;
+///   }
+///
+///   use double_trait::Dummy;
+///   assert_eq!(None, Dummy.find());
+///   Dummy.notify(); // Panics with "not implemented: MyTrait::notify"
+///   ```
+///
+/// * `lenient`: The opposite of `strict`. Default implementations return `Default::default()` for
+///   return types implementing `Default`, so adding this never causes compile errors for types
+///   without it. Only these types still panic. Useful for smoke tests. Like `strict`, this can be
+///   overridden per method using `#[dummies(lenient)]` or `#[dummies(lenient = false)]`.
+///
+///   ```
+///   #[double_trait::dummies(lenient)]
+///   trait MyTrait {
+///       fn answer(&self) -> i32;
+///       fn name(&self) -> Result<String, std::io::Error>;
+///   }
+///
+///   use double_trait::Dummy;
+///   assert_eq!(0, Dummy.answer());
+///   assert_eq!("", Dummy.name().unwrap());
+///   ```
+///
+/// * `doc`: Appends a line like "Dummy default: returns `None`." to the documentation of each
+///   method with a generated default implementation. `doc_summary` adds a table summarizing all
+///   generated defaults to the documentation of the trait instead. Both are opt-in, so the
+///   documentation of production code stays clean.
+///
+///   ```
+///   #[double_trait::dummies(doc, doc_summary)]
+///   trait MyTrait {
+///       /// Looks up the answer.
+///       fn find(&self) -> Option<i32>;
+///   }
+///   ```
+///
+/// * `debug`: Emits the expansion of the macro together with the default body strategy chosen for
+///   each method as a compile time warning. Helps to understand why a method got a certain default
+///   implementation, without reaching for `cargo expand`. Remove it again once you are done.
+///
+/// * `fill`: Leaves the trait completely free of default implementations. Instead, impls of the
+///   trait annotated with [`macro@fill`] receive the generated implementations for every method
+///   they do not implement themselves. The trait is still implemented for the dummy. Only impls
+///   within the crate defining the trait can be filled, see [`macro@fill`] for details.
+///
+///   ```
+///   #[double_trait::dummies(fill)]
+///   trait MyTrait {
+///       fn answer(&self) -> i32;
+///       fn find(&self) -> Option<i32>;
+///   }
+///
+///   struct Stub;
+///
+///   #[double_trait::fill]
+///   impl MyTrait for Stub {
+///       fn answer(&self) -> i32 {
+///           42
+///       }
+///   }
+///
+///   assert_eq!(None, Stub.find());
+///   ```
+///
+/// * `allow_unsafe`: Required to implement an `unsafe trait` for the dummy. Since the dummy does
+///   nothing but panic or return trivial values, it is up to you to decide whether this upholds
+///   the safety contract of the trait. The dummy is then implemented using `unsafe impl`. Methods
+///   declared as `unsafe fn` receive default implementations like any other method.
 ///
+///   ```
+///   /// # Safety
+///   ///
+///   /// `id` must be unique for every device.
+///   #[double_trait::dummies(allow_unsafe)]
+///   unsafe trait RawDevice {
+///       fn id(&self) -> Option<u32>;
+///       unsafe fn write(&mut self, address: usize, value: u8);
+///   }
+///   ```
+///
+/// * `poll = ready`: Methods returning `Poll` return `Poll::Ready` with the default behavior of
+///   the inner type, rather than `Poll::Pending`. `poll = pending` restates the default.
+///
+///   ```
+///   use std::{pin::Pin, task::{Context, Poll}};
+///
+///   #[double_trait::dummies(poll = ready)]
+///   trait RawSink {
+///       fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), String>>;
+///   }
+///   ```
 #[proc_macro_attribute]
 pub fn dummies(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
+    let mut args = Args::default();
+    let args_parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with args_parser);
     let item = parse_macro_input!(item as ItemTrait);
 
-    let output = dummies::expand(item).unwrap_or_else(Error::into_compile_error);
+    let output = dummies::expand(item, &args).unwrap_or_else(Error::into_compile_error);
+
+    proc_macro::TokenStream::from(output)
+}
+
+/// Generates dummies for a trait defined elsewhere, e.g. in the standard library or another crate.
+///
+/// Since [`macro@dummies`] can only be applied to the definition of a trait, `remote` takes a
+/// restated declaration of the foreign trait instead. Only the restated items need to be listed,
+/// items with default implementations in the foreign trait can be omitted. For a trait `Name` it
+/// generates:
+///
+/// * A helper trait `DoubleName` with the restated items, which is treated exactly like a trait
+///   annotated with [`macro@dummies`]. Arguments can be passed using a `#[dummies(...)]`
+///   attribute on the declaration.
+/// * A wrapper `RemoteName<Inner>`, which implements the foreign trait for any `Inner` implementing
+///   `DoubleName`, by forwarding to it.
+///
+/// Use `trait path::Name as Other` to generate `DoubleOther` and `RemoteOther` instead.
+///
+/// ```
+/// use std::io::{self, Read};
+///
+/// double_trait::remote! {
+///     trait std::io::Read {
+///         fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+///     }
+/// }
+///
+/// // Only override the methods relevant for the test
+/// struct Empty;
+///
+/// impl DoubleRead for Empty {
+///     fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+///         Ok(0)
+///     }
+/// }
+///
+/// let mut text = String::new();
+/// RemoteRead(Empty).read_to_string(&mut text).unwrap();
+/// assert_eq!("", text);
+/// ```
+#[proc_macro]
+pub fn remote(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let remote = parse_macro_input!(input as RemoteTrait);
+
+    let output = remote::expand(remote).unwrap_or_else(Error::into_compile_error);
+
+    proc_macro::TokenStream::from(output)
+}
+
+/// Adds generated default implementations for all methods missing from an impl of a trait
+/// annotated with `#[dummies(fill)]`. See the `fill` argument of [`macro@dummies`].
+///
+/// The trait must be in scope the same way it is for the impl, since `dummies(fill)` describes it
+/// to this attribute through a hidden macro sharing the name of the trait. This comes with two
+/// limitations:
+///
+/// * The hidden macro is visible at most within the crate defining the trait, even if the trait is
+///   `pub`. Impls in other crates, including integration tests, can not be filled.
+/// * The hidden macro lives in the macro namespace. It clashes with any other macro of the same
+///   name in the same module, e.g. a derive or attribute macro named like the trait.
+#[proc_macro_attribute]
+pub fn fill(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return Error::new_spanned(attr, "`fill` does not take any arguments")
+            .into_compile_error()
+            .into();
+    }
+    let item_impl = parse_macro_input!(item as ItemImpl);
+
+    let output = fill::expand_fill_attribute(item_impl).unwrap_or_else(Error::into_compile_error);
+
+    proc_macro::TokenStream::from(output)
+}
+
+/// Invoked by the macro generated by `#[dummies(fill)]`. Not part of the public API.
+#[doc(hidden)]
+#[proc_macro]
+pub fn fill_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as FillInput);
+
+    let output = fill::expand_fill_impl(input).unwrap_or_else(Error::into_compile_error);
 
     proc_macro::TokenStream::from(output)
 }
@@ -0,0 +1,322 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, FnArg, GenericParam, Generics, Ident, ItemTrait, Path, ReceiverKind, Token,
+    TraitItem, TraitItemFn, Visibility, braced,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    token::{Brace, Plus},
+};
+
+use crate::{args::Args, dummies};
+
+/// Input of [`crate::remote`]. A restated declaration of a trait defined elsewhere, e.g.
+/// `trait std::io::Read { fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>; }`.
+pub struct RemoteTrait {
+    /// Arguments passed via `#[dummies(...)]` on the declaration.
+    args: Args,
+    /// Remaining attributes of the declaration. Forwarded to the helper trait.
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    unsafety: Option<Token![unsafe]>,
+    /// Path to the foreign trait, e.g. `std::io::Read`.
+    path: Path,
+    generics: Generics,
+    /// Name used to derive the names of the generated items. Defaults to the name of the foreign
+    /// trait. Set via `trait path::Trait as Name`.
+    name: Ident,
+    supertraits: Punctuated<syn::TypeParamBound, Plus>,
+    items: Vec<TraitItem>,
+}
+
+impl Parse for RemoteTrait {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let mut args = Args::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            let is_dummies = attr.path().is_ident("dummies");
+            if is_dummies && result.is_ok() {
+                result = attr.parse_nested_meta(|meta| args.parse(meta));
+            }
+            !is_dummies
+        });
+        result?;
+        let vis = input.parse()?;
+        let unsafety = input.parse()?;
+        input.parse::<Token![trait]>()?;
+        let path = Path::parse_mod_style(input)?;
+        let mut generics: Generics = input.parse()?;
+        let name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        } else {
+            let last = path.segments.last();
+            let last = last.ok_or_else(|| input.error("expected path to a trait"))?;
+            last.ident.clone()
+        };
+        let mut supertraits = Punctuated::new();
+        if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            while !input.peek(Token![where]) && !input.peek(Brace) {
+                supertraits.push_value(input.parse()?);
+                if !input.peek(Token![+]) {
+                    break;
+                }
+                supertraits.push_punct(input.parse()?);
+            }
+        }
+        generics.where_clause = input.parse()?;
+        let content;
+        braced!(content in input);
+        let mut items = Vec::new();
+        while !content.is_empty() {
+            items.push(content.parse()?);
+        }
+        Ok(RemoteTrait {
+            args,
+            attrs,
+            vis,
+            unsafety,
+            path,
+            generics,
+            name,
+            supertraits,
+            items,
+        })
+    }
+}
+
+/// The main implementation of [`crate::remote`]. Generates a local helper trait with default
+/// implementations, a wrapper struct and an implementation of the foreign trait for the wrapper,
+/// which forwards to the helper trait.
+pub fn expand(remote: RemoteTrait) -> syn::Result<TokenStream> {
+    let RemoteTrait {
+        args,
+        attrs,
+        vis,
+        unsafety,
+        path,
+        generics,
+        name,
+        supertraits,
+        items,
+    } = remote;
+    let helper = format_ident!("Double{name}");
+    let wrapper = format_ident!("Remote{name}");
+    let where_clause = &generics.where_clause;
+    let colon = (!supertraits.is_empty()).then(|| quote! { : });
+    let helper_trait: ItemTrait = parse_quote! {
+        #(#attrs)*
+        #vis #unsafety trait #helper #generics #colon #supertraits #where_clause {
+            #(#items)*
+        }
+    };
+
+    // The helper trait is treated just like a trait annotated with `dummies`.
+    let helper_trait_with_dummies = dummies::expand(helper_trait, &args)?;
+
+    // Orphan rules forbid implementing the foreign trait for every implementation of the helper
+    // trait, so we implement it for a wrapper instead.
+    let inner = Ident::new("Inner", name.span());
+    let trait_generics = generic_arguments(&generics);
+    let mut impl_generics = generics.clone();
+    impl_generics.params.push(parse_quote!(#inner));
+    impl_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#inner: #helper #trait_generics));
+    let (impl_generics, _, impl_where_clause) = impl_generics.split_for_impl();
+    let forwarded = items
+        .iter()
+        .map(|item| forward_item(item, &inner, &helper, &trait_generics))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let token_stream = quote! {
+        #helper_trait_with_dummies
+
+        /// Implements the foreign trait by forwarding to the wrapped implementation of the helper
+        /// trait.
+        #[derive(Debug, Default, Clone)]
+        #vis struct #wrapper<#inner>(pub #inner);
+
+        #unsafety impl #impl_generics #path #trait_generics for #wrapper<#inner> #impl_where_clause {
+            #(#forwarded)*
+        }
+    };
+    Ok(token_stream)
+}
+
+/// Generic arguments referring to the generic parameters, e.g. `<'a, T>` for `<'a, T: Clone>`.
+fn generic_arguments(generics: &Generics) -> TokenStream {
+    if generics.params.is_empty() {
+        return TokenStream::new();
+    }
+    let args = generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(lifetime) => {
+            let lifetime = &lifetime.lifetime;
+            quote! { #lifetime }
+        }
+        GenericParam::Type(ty) => {
+            let ident = &ty.ident;
+            quote! { #ident }
+        }
+        GenericParam::Const(konst) => {
+            let ident = &konst.ident;
+            quote! { #ident }
+        }
+    });
+    quote! { <#(#args),*> }
+}
+
+/// Item of the foreign trait implementation, forwarding to the helper trait.
+fn forward_item(
+    item: &TraitItem,
+    inner: &Ident,
+    helper: &Ident,
+    trait_generics: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let helper = quote! { <#inner as #helper #trait_generics> };
+    let forwarded = match item {
+        TraitItem::Const(konst) => {
+            let ident = &konst.ident;
+            let ty = &konst.ty;
+            quote! { const #ident: #ty = #helper::#ident; }
+        }
+        TraitItem::Type(ty) => {
+            let ident = &ty.ident;
+            let generics = &ty.generics;
+            let where_clause = &ty.generics.where_clause;
+            let args = generic_arguments(generics);
+            quote! { type #ident #generics = #helper::#ident #args #where_clause; }
+        }
+        TraitItem::Fn(fn_item) => forward_function(fn_item, &helper)?,
+        other => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "only methods, associated types and constants are supported by `remote`",
+            ));
+        }
+    };
+    Ok(forwarded)
+}
+
+fn forward_function(fn_item: &TraitItemFn, helper: &TokenStream) -> syn::Result<TokenStream> {
+    let mut sig = fn_item.sig.clone();
+    let mut call_args = Vec::new();
+    for (index, arg) in sig.inputs.iter_mut().enumerate() {
+        match arg {
+            FnArg::Receiver(receiver) => {
+                let forwarded = match &receiver.kind {
+                    ReceiverKind::Value => {
+                        // A mutable receiver taken by value is a binding of the wrapper, not of the
+                        // inner value.
+                        receiver.mutability = None;
+                        quote! { self.0 }
+                    }
+                    ReceiverKind::Reference(_and, _lifetime, mutability) => {
+                        quote! { &#mutability self.0 }
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            receiver,
+                            "`remote` only supports the receivers `self`, `&self` and `&mut self`",
+                        ));
+                    }
+                };
+                call_args.push(forwarded);
+            }
+            FnArg::Typed(pat_type) => {
+                // Patterns may be arbitrary, so we bind each argument to a name of our own.
+                let ident = format_ident!("arg{index}");
+                *pat_type.pat = parse_quote!(#ident);
+                call_args.push(quote! { #ident });
+            }
+        }
+    }
+    let fn_name = &sig.ident;
+    let call = quote! { #helper::#fn_name(#(#call_args),*) };
+    let call = if sig.asyncness.is_some() {
+        quote! { #call.await }
+    } else {
+        call
+    };
+    Ok(quote! {
+        #sig {
+            #call
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::parse2;
+
+    use super::{RemoteTrait, expand};
+
+    #[test]
+    fn forward_foreign_trait_to_helper_trait() {
+        // Given a restated declaration of a foreign trait
+        let remote: RemoteTrait = parse2(quote! {
+            trait std::io::Read {
+                fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>;
+            }
+        })
+        .unwrap();
+
+        // When expanding it
+        let output = expand(remote).unwrap();
+
+        // Then a wrapper implements the foreign trait by forwarding to the helper trait
+        let expected = quote! {
+            /// Implements the foreign trait by forwarding to the wrapped implementation of the helper
+            /// trait.
+            #[derive(Debug, Default, Clone)]
+            struct RemoteRead<Inner>(pub Inner);
+
+            impl<Inner> std::io::Read for RemoteRead<Inner> where Inner: DoubleRead {
+                fn read(&mut self, arg1: &mut [u8]) -> std::io::Result<usize> {
+                    <Inner as DoubleRead>::read(&mut self.0, arg1)
+                }
+            }
+        };
+        let output = output.to_string();
+        assert!(output.contains("trait DoubleRead"));
+        assert!(output.contains("impl DoubleRead for double_trait :: __private :: Dummy"));
+        assert!(output.ends_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn rename_generated_items() {
+        // Given a restated declaration of a foreign trait with a new name
+        let remote: RemoteTrait = parse2(quote! {
+            pub trait other::Service<Request> as MyService {
+                type Response;
+                fn call(&mut self, request: Request) -> Self::Response;
+            }
+        })
+        .unwrap();
+
+        // When expanding it
+        let output = expand(remote).unwrap().to_string();
+
+        // Then helper trait and wrapper are named after the new name
+        assert!(output.contains("pub trait DoubleMyService < Request >"));
+        assert!(output.contains("pub struct RemoteMyService < Inner >"));
+        assert!(
+            output.contains(
+                &quote! {
+                    impl<Request, Inner> other::Service<Request> for RemoteMyService<Inner>
+                    where
+                        Inner: DoubleMyService<Request>
+                }
+                .to_string()
+            )
+        );
+        assert!(output.contains(
+            &quote! { type Response = <Inner as DoubleMyService<Request> >::Response; }.to_string()
+        ));
+    }
+}
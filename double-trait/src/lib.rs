@@ -1,5 +1,13 @@
-// Reexport the double macro from our derive crate
-pub use double_derive::dummies;
+mod registry;
+mod strict;
+
+use std::{rc::Rc, sync::Arc};
+
+// Reexport the double macros from our derive crate
+pub use double_derive::{dummies, fill, remote};
+
+pub use self::registry::{UnimplementedCall, UnimplementedCallsGuard};
+pub use self::strict::{StrictModeGuard, strict_mode};
 
 /// A general purpose test Dummy. Implements any interface annotadet with the [`dummies`] macro as
 /// well as a number of traits from the `std` namespace.
@@ -28,3 +36,137 @@ pub use double_derive::dummies;
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Dummy;
+
+impl Dummy {
+    /// A boxed trait object of `Dummy`. `T` is usually `dyn MyTrait` for a trait annotated with
+    /// `#[dummies(dyn_compatible)]`.
+    ///
+    /// ```
+    /// use double_trait::{dummies, Dummy};
+    ///
+    /// #[dummies(dyn_compatible)]
+    /// trait MyTrait {
+    ///     fn answer(&self) -> Option<i32>;
+    /// }
+    ///
+    /// let my_trait = Dummy::boxed::<dyn MyTrait>();
+    /// assert_eq!(None, my_trait.answer());
+    /// ```
+    pub fn boxed<T>() -> Box<T>
+    where
+        T: ?Sized,
+        Self: IntoDyn<T>,
+    {
+        Dummy.into_boxed()
+    }
+
+    /// A trait object of `Dummy` behind an [`Arc`]. See [`Dummy::boxed`].
+    pub fn arc<T>() -> Arc<T>
+    where
+        T: ?Sized,
+        Self: IntoDyn<T>,
+    {
+        Dummy.into_arc()
+    }
+
+    /// A trait object of `Dummy` behind an [`Rc`]. See [`Dummy::boxed`].
+    pub fn rc<T>() -> Rc<T>
+    where
+        T: ?Sized,
+        Self: IntoDyn<T>,
+    {
+        Dummy.into_rc()
+    }
+}
+
+/// Conversion of a dummy into a trait object `T`, e.g. `dyn MyTrait`. Implemented by [`dummies`] if
+/// the annotated trait is dyn compatible and `dyn_compatible` is passed as an argument.
+///
+/// For `Dummy` itself it is more convenient to use [`Dummy::boxed`], [`Dummy::arc`] or
+/// [`Dummy::rc`].
+pub trait IntoDyn<T: ?Sized> {
+    /// Moves the dummy into a [`Box`].
+    fn into_boxed(self) -> Box<T>;
+
+    /// Moves the dummy into an [`Arc`].
+    fn into_arc(self) -> Arc<T>;
+
+    /// Moves the dummy into an [`Rc`].
+    fn into_rc(self) -> Rc<T>;
+}
+
+/// Not part of the public API. Items in here are used by the code generated by [`dummies`], so
+/// crates using `double-trait` do not need to depend on them directly.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::{
+        Dummy, IntoDyn, registry::report as report_unimplemented, strict::is_strict_mode,
+    };
+    pub use double_derive::fill_impl;
+
+    use std::{fmt::Debug, marker::PhantomData};
+
+    /// Formats arguments for panic messages in verbose mode. Wraps a reference to the argument so
+    /// that [`ViaDebug`] is preferred over [`ViaFallback`] by method resolution, if the argument
+    /// implements [`Debug`].
+    pub struct DebugArg<'a, T: ?Sized>(pub &'a T);
+
+    pub trait ViaDebug {
+        fn debug_arg(&self) -> String;
+    }
+
+    impl<T: Debug + ?Sized> ViaDebug for DebugArg<'_, T> {
+        fn debug_arg(&self) -> String {
+            format!("{:?}", self.0)
+        }
+    }
+
+    pub trait ViaFallback {
+        fn debug_arg(&self) -> String;
+    }
+
+    impl<T: ?Sized> ViaFallback for &DebugArg<'_, T> {
+        fn debug_arg(&self) -> String {
+            String::from("_")
+        }
+    }
+
+    /// Constructs return values in lenient mode. Method resolution prefers [`ViaDefault`] over
+    /// [`ViaUnimplemented`], if `T` implements [`Default`].
+    pub struct LenientDefault<T>(PhantomData<fn() -> T>);
+
+    impl<T> LenientDefault<T> {
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<T> Default for LenientDefault<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub trait ViaDefault<T> {
+        fn lenient_default(&self, unimplemented: impl FnOnce() -> T) -> T;
+    }
+
+    impl<T: Default> ViaDefault<T> for LenientDefault<T> {
+        fn lenient_default(&self, _unimplemented: impl FnOnce() -> T) -> T {
+            T::default()
+        }
+    }
+
+    pub trait ViaUnimplemented<T> {
+        fn lenient_default(&self, unimplemented: impl FnOnce() -> T) -> T;
+    }
+
+    impl<T> ViaUnimplemented<T> for &LenientDefault<T> {
+        fn lenient_default(&self, unimplemented: impl FnOnce() -> T) -> T {
+            unimplemented()
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    pub use futures_util;
+}
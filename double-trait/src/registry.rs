@@ -0,0 +1,166 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    marker::PhantomData,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+static GLOBAL: Mutex<Vec<UnimplementedCall>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static LOCAL: RefCell<Vec<UnimplementedCall>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An invocation of a default implementation generated by [`crate::dummies`], which could not
+/// provide a sensible value and therefore invoked `unimplemented!` (or whatever has been configured
+/// using `on_unimplemented`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnimplementedCall {
+    /// Name of the trait, e.g. `MyTrait`.
+    pub trait_name: &'static str,
+    /// Name of the method, e.g. `answer`.
+    pub method: &'static str,
+    /// Name of the thread the method has been invoked on, if it has one.
+    pub thread: Option<String>,
+}
+
+impl Display for UnimplementedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.trait_name, self.method)?;
+        if let Some(thread) = &self.thread {
+            write!(f, " on thread '{thread}'")?;
+        }
+        Ok(())
+    }
+}
+
+/// Invoked by the generated code right before panicking.
+pub fn report(trait_name: &'static str, method: &'static str) {
+    let call = UnimplementedCall {
+        trait_name,
+        method,
+        thread: thread::current().name().map(str::to_owned),
+    };
+    LOCAL.with(|local| local.borrow_mut().push(call.clone()));
+    lock_global().push(call);
+}
+
+/// Panics on drop, if any unimplemented call has been registered since the guard has been created,
+/// even if the panic it caused has been caught. Does nothing, if the current thread is already
+/// panicking.
+///
+/// A guard created with [`UnimplementedCallsGuard::new`] only considers calls on the thread it has
+/// been created on. This way tests running in parallel do not interfere with each other.
+///
+/// ```should_panic
+/// use double_trait::{dummies, Dummy, UnimplementedCallsGuard};
+///
+/// #[dummies]
+/// trait MyTrait {
+///     fn answer(&self) -> i32;
+/// }
+///
+/// let _guard = UnimplementedCallsGuard::new();
+/// // Swallow the panic of the dummy
+/// let _ = std::panic::catch_unwind(|| Dummy.answer());
+/// // `_guard` panics on drop, since `MyTrait::answer` has been invoked
+/// ```
+///
+/// Calls on spawned threads are only detected by a guard created with
+/// [`UnimplementedCallsGuard::global`].
+#[derive(Debug)]
+pub struct UnimplementedCallsGuard {
+    scope: Scope,
+    start: usize,
+    // The position in the thread local registry is meaningless on any other thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Scope {
+    Thread,
+    Process,
+}
+
+impl UnimplementedCallsGuard {
+    /// Only considers unimplemented calls on the current thread.
+    pub fn new() -> Self {
+        Self::with_scope(Scope::Thread)
+    }
+
+    /// Considers unimplemented calls on any thread of this process, e.g. in spawned threads or
+    /// tasks.
+    ///
+    /// This does not isolate tests from each other. Tests usually run in parallel in the same
+    /// process, so the guard also detects calls made by any other test while it is alive. Run
+    /// tests using such a guard with `--test-threads=1`, or accept that they may fail spuriously.
+    ///
+    /// ```should_panic
+    /// use double_trait::{dummies, Dummy, UnimplementedCallsGuard};
+    ///
+    /// #[dummies]
+    /// trait MyTrait {
+    ///     fn answer(&self) -> i32;
+    /// }
+    ///
+    /// let _guard = UnimplementedCallsGuard::global();
+    /// // A thread which swallows the panic of the dummy
+    /// let _ = std::thread::spawn(|| Dummy.answer()).join();
+    /// // `_guard` panics on drop, since `MyTrait::answer` has been invoked
+    /// ```
+    pub fn global() -> Self {
+        Self::with_scope(Scope::Process)
+    }
+
+    fn with_scope(scope: Scope) -> Self {
+        let start = match scope {
+            Scope::Thread => LOCAL.with(|local| local.borrow().len()),
+            Scope::Process => lock_global().len(),
+        };
+        Self {
+            scope,
+            start,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Unimplemented calls within the scope of the guard since it has been created.
+    pub fn calls(&self) -> Vec<UnimplementedCall> {
+        match self.scope {
+            Scope::Thread => LOCAL.with(|local| local.borrow()[self.start..].to_vec()),
+            Scope::Process => lock_global()[self.start..].to_vec(),
+        }
+    }
+}
+
+impl Default for UnimplementedCallsGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for UnimplementedCallsGuard {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            assert_calls_empty(&self.calls());
+        }
+    }
+}
+
+fn assert_calls_empty(calls: &[UnimplementedCall]) {
+    if calls.is_empty() {
+        return;
+    }
+    let calls: Vec<_> = calls.iter().map(ToString::to_string).collect();
+    panic!(
+        "Default implementations of dummies have been invoked: {}",
+        calls.join(", ")
+    );
+}
+
+fn lock_global() -> std::sync::MutexGuard<'static, Vec<UnimplementedCall>> {
+    // Reporting never panics while holding the lock, but we do not want to turn a failing test into
+    // a confusing poison error anyway.
+    GLOBAL.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,46 @@
+use std::cell::Cell;
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Enables strict mode for the current thread, until the returned guard is dropped.
+///
+/// In strict mode all default implementations generated by [`crate::dummies`] panic, even those
+/// which would otherwise return a sensible value like `()`, `None` or `Ok(())`. This is equivalent
+/// to annotating every trait with `#[dummies(strict)]`, but only for a single test.
+///
+/// ```should_panic
+/// use double_trait::{dummies, strict_mode, Dummy};
+///
+/// #[dummies]
+/// trait MyTrait {
+///     fn notify(&self);
+/// }
+///
+/// let _strict = strict_mode();
+/// Dummy.notify(); // Panics with "not implemented: MyTrait::notify"
+/// ```
+///
+/// Strict mode is not inherited by spawned threads.
+pub fn strict_mode() -> StrictModeGuard {
+    let previous = STRICT.with(|strict| strict.replace(true));
+    StrictModeGuard { previous }
+}
+
+/// Returned by [`strict_mode`]. Restores the previous mode on drop.
+#[derive(Debug)]
+pub struct StrictModeGuard {
+    previous: bool,
+}
+
+impl Drop for StrictModeGuard {
+    fn drop(&mut self) {
+        STRICT.with(|strict| strict.set(self.previous));
+    }
+}
+
+/// Invoked by the generated code to decide whether safe defaults should panic.
+pub fn is_strict_mode() -> bool {
+    STRICT.with(Cell::get)
+}
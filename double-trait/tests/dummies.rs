@@ -1,5 +1,7 @@
 // We are more interested that the code compiles and  not so much in the actual functionality.
 #![allow(dead_code)]
+// Some tests are about explicit `impl Future` return types, rather than `async fn`.
+#![allow(clippy::manual_async_fn)]
 
 use std::error::Error;
 
@@ -270,3 +272,1014 @@ async fn impl_stream_return() {
     // Then the stream is empty
     assert!(values.is_empty())
 }
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn impl_stream_of_impl_future() {
+    use futures_util::{Stream, StreamExt};
+
+    // Given a trait method which returns a stream whose items are `impl Future` themselves
+    #[dummies]
+    trait MyTrait {
+        fn answer(&self) -> impl Stream<Item = impl Future<Output = u8>>;
+    }
+
+    // When invoking the default implementation of `answer`
+    let values: Vec<_> = Dummy.answer().collect().await;
+
+    // Then the stream is empty
+    assert!(values.is_empty())
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn impl_stream_send_unpin() {
+    use futures_util::{Stream, StreamExt};
+
+    // Given a trait method which returns a stream with additional `Send` and `Unpin` bounds
+    #[dummies]
+    trait MyTrait {
+        fn answer(&self) -> impl Stream<Item = i32> + Send + Unpin;
+    }
+
+    // When polling the stream without pinning it first
+    let mut stream = Dummy.answer();
+    let next = stream.next().await;
+
+    // Then the stream is exhausted immediately
+    assert!(next.is_none())
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn impl_stream_does_not_require_futures_util_in_scope() {
+    // Users of `double-trait` should not need to depend on `futures-util` themselves. We simulate
+    // this by shadowing the crate name with an empty module. Code generated by `dummies` which
+    // would refer to `futures_util` directly would fail to compile.
+    #[allow(unused)]
+    mod futures_util {}
+    use ::futures_util::{Stream, StreamExt};
+
+    // Given a trait method which returns an `impl Stream`
+    #[dummies]
+    trait MyTrait {
+        fn answer(&self) -> impl Stream<Item = i32>;
+    }
+
+    // When invoking the default implementation of `answer`
+    let values: Vec<_> = Dummy.answer().collect().await;
+
+    // Then the stream is empty
+    assert!(values.is_empty())
+}
+
+#[test]
+fn custom_crate_path() {
+    // Given `double_trait` is only reachable under a different name. We simulate this by shadowing
+    // the crate name with an empty module.
+    #[allow(unused)]
+    mod double_trait {}
+    use ::double_trait as renamed;
+
+    // When annotating a trait with `dummies` pointing to the renamed crate
+    #[renamed::dummies(crate = renamed)]
+    trait MyTrait {
+        type Item;
+
+        fn answer(&self);
+    }
+
+    // Then the generated code compiles and `Dummy` implements `MyTrait`
+    fn use_trait(_: impl MyTrait) {}
+    use_trait(renamed::Dummy);
+}
+
+#[test]
+fn custom_dummy_type() {
+    // Given a user supplied dummy type
+    #[derive(Debug)]
+    struct MyDummy;
+
+    // When annotating a trait with `dummies` using `MyDummy`
+    #[dummies(dummy = MyDummy)]
+    trait MyTrait {
+        type Item: std::fmt::Debug;
+
+        fn answer(&self) -> Option<i32>;
+    }
+
+    // Then `MyDummy` implements `MyTrait` using the default implementations
+    assert_eq!(None, MyDummy.answer());
+}
+
+#[test]
+fn no_dummy_allows_implementing_trait_for_dummy_manually() {
+    // Given a trait annotated with `dummies(no_dummy)`
+    #[dummies(no_dummy)]
+    trait MyTrait {
+        fn answer(&self) -> i32;
+    }
+
+    // When implementing it for `Dummy` manually
+    impl MyTrait for Dummy {
+        fn answer(&self) -> i32 {
+            42
+        }
+    }
+
+    // Then there is no conflicting implementation
+    assert_eq!(42, Dummy.answer());
+}
+
+#[test]
+fn dedicated_dummy_struct() {
+    // Given two traits with conflicting method names, each with its own dummy struct
+    #[dummies(dummy_struct)]
+    trait Question {
+        fn answer(&self) -> Option<i32>;
+    }
+    #[dummies(dummy_struct = Riddle)]
+    trait Puzzle {
+        fn answer(&self) -> Option<i32>;
+    }
+
+    // When invoking `answer` on each of them
+    let question = QuestionDummy.answer();
+    let puzzle = Riddle.answer();
+
+    // Then both calls are unambiguous and the dummies can be told apart in debug output
+    assert_eq!(None, question);
+    assert_eq!(None, puzzle);
+    assert_eq!("QuestionDummy", format!("{:?}", QuestionDummy));
+    assert_eq!("Riddle", format!("{:?}", Riddle));
+}
+
+#[test]
+fn box_dedicated_dummy_struct() {
+    // Given a dyn compatible trait with a dedicated dummy struct
+    #[dummies(dummy_struct)]
+    trait MyTrait {
+        fn answer(&self) -> Vec<i32>;
+    }
+
+    // When converting the dummy into a trait object
+    let boxed: Box<dyn MyTrait> = MyTraitDummy.into();
+
+    // Then the trait object uses the default implementations
+    assert!(boxed.answer().is_empty());
+}
+
+#[test]
+fn dedicated_dummy_struct_for_sized_trait() {
+    // Given a trait requiring `Self: Sized`, with a dedicated dummy struct
+    #[dummies(dummy_struct)]
+    trait NotDyn
+    where
+        Self: Sized,
+    {
+        fn answer(&self) -> Vec<i32>;
+    }
+
+    // When invoking a method on the dummy
+    let answer = NotDynDummy.answer();
+
+    // Then it compiles, even though there is no `dyn NotDyn`
+    assert!(answer.is_empty());
+}
+
+#[test]
+fn dummy_as_trait_object() {
+    use std::rc::Rc;
+
+    // Given a dyn compatible trait. Methods which require `Self: Sized` do not interfere.
+    #[dummies(dyn_compatible)]
+    trait MyTrait {
+        fn answer(&self) -> Option<i32>;
+
+        fn new() -> Self
+        where
+            Self: Sized;
+
+        fn generic<T: std::fmt::Debug>(&self, value: T) -> Vec<T>
+        where
+            Self: Sized;
+    }
+
+    // When converting `Dummy` into trait objects
+    let boxed = Dummy::boxed::<dyn MyTrait>();
+    let arc = Dummy::arc::<dyn MyTrait + Send + Sync>();
+    let rc: Rc<dyn MyTrait> = Dummy::rc();
+
+    // Then the trait objects use the default implementations
+    assert_eq!(None, boxed.answer());
+    assert_eq!(None, arc.answer());
+    assert_eq!(None, rc.answer());
+}
+
+#[test]
+fn verbose_panic_message_mentions_arguments_and_caller() {
+    // Given a trait annotated with `dummies(verbose)` and a method taking an argument which
+    // implements `Debug` and one which does not
+    struct NoDebug;
+    #[dummies(verbose)]
+    trait MyTrait {
+        fn answer(&self, question: &str, no_debug: NoDebug) -> i32;
+    }
+
+    // When invoking the default implementation of `answer`
+    let line = line!() + 1;
+    let result = std::panic::catch_unwind(|| Dummy.answer("Why?", NoDebug));
+
+    // Then the panic message mentions the arguments and the line of the invocation
+    let payload = result.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    let expected_prefix = format!(
+        "not implemented: MyTrait::answer(question: \"Why?\", no_debug: _) called at {}:{line}:",
+        file!()
+    );
+    assert!(message.starts_with(&expected_prefix), "{message}");
+}
+
+#[test]
+fn verbose_mode_with_async_methods() {
+    // Compilation test. Arguments must not be captured by the returned futures or iterators, so
+    // `Send` bounds hold even for arguments which are not `Send`.
+    use std::rc::Rc;
+
+    #[dummies(verbose)]
+    trait MyTrait {
+        async fn answer(&self, question: String) -> i32;
+
+        fn future(&self, not_send: Rc<i32>) -> impl Future<Output = i32> + Send;
+
+        fn iter(&self, not_send: Rc<i32>) -> impl Iterator<Item = i32> + Send;
+    }
+}
+
+#[test]
+fn verbose_panic_message_with_pattern_arguments() {
+    // Given a trait annotated with `dummies(verbose)` and a method destructuring its argument
+    #[dummies(verbose)]
+    trait MyTrait {
+        fn pair(&self, (a, b): (i32, i32), mut c: i32) -> i32;
+    }
+
+    // When invoking the default implementation of `pair`
+    let result = std::panic::catch_unwind(|| Dummy.pair((1, 2), 3));
+
+    // Then the destructured argument is shown as `_`. Clippy also checks that there are no unused
+    // variables in the generated code.
+    let payload = result.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("not implemented: MyTrait::pair(_, c: 3) called at"),
+        "{message}"
+    );
+}
+
+#[test]
+#[should_panic(expected = "not yet implemented: MyTrait::answer")]
+fn on_unimplemented_todo() {
+    // Given a trait annotated to use `todo!` for unimplemented methods
+    #[dummies(on_unimplemented = todo!)]
+    trait MyTrait {
+        fn answer(&self) -> Result<i32, Box<dyn Error>>;
+    }
+
+    // When invoking the default implementation of `answer`
+    let _ = Dummy.answer();
+
+    // Then the panic message stems from `todo!`
+}
+
+#[test]
+fn on_unimplemented_custom_handler() {
+    use std::cell::RefCell;
+
+    // Given a handler recording the message of the unimplemented method into a thread local
+    thread_local! {
+        static LAST_CALL: RefCell<Option<String>> = const { RefCell::new(None) };
+    }
+    fn record<T: Default>(message: &str) -> T {
+        LAST_CALL.with(|last| *last.borrow_mut() = Some(message.to_owned()));
+        T::default()
+    }
+
+    #[dummies(on_unimplemented = record)]
+    trait MyTrait {
+        fn answer(&self) -> impl Iterator<Item = i32>;
+
+        fn question(&self) -> i32;
+    }
+
+    // When invoking the default implementation of `question`
+    let value = Dummy.question();
+
+    // Then the handler is invoked instead of panicking
+    assert_eq!(0, value);
+    assert_eq!(
+        Some("MyTrait::question".to_owned()),
+        LAST_CALL.with(|last| last.borrow_mut().take())
+    );
+}
+
+#[test]
+fn unimplemented_calls_are_registered_even_if_panic_is_swallowed() {
+    use double_trait::UnimplementedCallsGuard;
+
+    // Given a trait with a method without sensible default, as well as a guard for the process and
+    // one for the current thread
+    #[dummies]
+    trait Swallowed {
+        fn answer(&self) -> i32;
+    }
+    let global = UnimplementedCallsGuard::global();
+    let local = UnimplementedCallsGuard::new();
+
+    // When invoking `answer` in a thread whose panic is swallowed
+    let _ = std::thread::Builder::new()
+        .name("swallowing".to_owned())
+        .spawn(|| Dummy.answer())
+        .unwrap()
+        .join();
+
+    // Then the call is registered globally, but not for the current thread. Other tests may run in
+    // parallel, so we only look for our own call.
+    assert!(
+        global
+            .calls()
+            .iter()
+            .any(|call| call.to_string() == "Swallowed::answer on thread 'swallowing'")
+    );
+    assert!(local.calls().is_empty());
+    // Dropping the global guard would panic, due to the call we just asserted on
+    std::mem::forget(global);
+}
+
+#[test]
+fn unimplemented_calls_guard_only_considers_its_own_thread() {
+    use double_trait::UnimplementedCallsGuard;
+
+    // Given a trait with a method without sensible default and a guard
+    #[dummies]
+    trait Guarded {
+        fn answer(&self) -> i32;
+    }
+    let guard = UnimplementedCallsGuard::new();
+
+    // When invoking `answer` in another thread as well as in the current one
+    let _ = std::thread::spawn(|| Dummy.answer()).join();
+    let _ = std::panic::catch_unwind(|| Dummy.answer());
+
+    // Then the guard only reports the call on the current thread
+    let calls: Vec<_> = guard.calls().iter().map(|call| call.method).collect();
+    assert_eq!(vec!["answer"], calls);
+    // Dropping the guard would panic, due to the call we just asserted on
+    std::mem::forget(guard);
+}
+
+#[test]
+#[should_panic(expected = "not implemented: MyTrait::notify")]
+fn strict_mode_panics_for_unit_return() {
+    // Given a trait annotated with `dummies(strict)`
+    #[dummies(strict)]
+    trait MyTrait {
+        fn notify(&self);
+    }
+
+    // When invoking a method, which would otherwise do nothing
+    Dummy.notify();
+
+    // Then it panics
+}
+
+#[test]
+fn strict_mode_can_be_overridden_per_method() {
+    // Given a trait annotated with `dummies(strict)` and a method opting out of it
+    #[dummies(strict)]
+    trait MyTrait {
+        #[dummies(strict = false)]
+        fn find(&self) -> Option<i32>;
+
+        fn items(&self) -> impl Iterator<Item = i32>;
+    }
+
+    // When invoking the method opting out of strict mode and the one which does not
+    let value = Dummy.find();
+    let items = std::panic::catch_unwind(|| Dummy.items().count());
+
+    // Then only the method opting out returns the safe default
+    assert_eq!(None, value);
+    assert!(items.is_err());
+}
+
+#[test]
+fn strict_mode_enabled_at_runtime() {
+    use double_trait::strict_mode;
+
+    // Given a trait with a method returning a `Vec`
+    #[dummies]
+    trait MyTrait {
+        fn items(&self) -> Vec<i32>;
+    }
+
+    // When invoking it with strict mode enabled at runtime
+    let result = {
+        let _strict = strict_mode();
+        std::panic::catch_unwind(|| Dummy.items())
+    };
+
+    // Then it panics, but only while strict mode is enabled
+    assert!(result.is_err());
+    assert!(Dummy.items().is_empty());
+}
+
+#[test]
+fn strict_mode_enabled_at_runtime_covers_lazy_and_lenient_defaults() {
+    use double_trait::strict_mode;
+
+    // Given a lazy default and a lenient default
+    #[dummies]
+    trait Lazy {
+        fn numbers(&self) -> impl Iterator<Item = i32>;
+    }
+    #[dummies(lenient)]
+    trait Lenient {
+        fn count(&self) -> u32;
+    }
+
+    // When invoking them with strict mode enabled at runtime
+    let (numbers, count) = {
+        let _strict = strict_mode();
+        (
+            std::panic::catch_unwind(|| Dummy.numbers().count()),
+            std::panic::catch_unwind(|| Dummy.count()),
+        )
+    };
+
+    // Then both panic
+    assert!(numbers.is_err());
+    assert!(count.is_err());
+}
+
+#[test]
+fn strict_mode_panics_even_if_handler_returns() {
+    use double_trait::strict_mode;
+
+    // Given a handler which returns rather than panics, and traits using it in strict mode or not
+    fn ignore<T: Default>(_message: &str) -> T {
+        T::default()
+    }
+    #[dummies(strict, on_unimplemented = ignore)]
+    trait Strict {
+        fn notify(&self);
+    }
+    #[dummies(on_unimplemented = ignore)]
+    trait Relaxed {
+        fn find(&self) -> Option<i32>;
+    }
+
+    // When invoking them in strict mode, enabled at compile time or at runtime
+    let notify = std::panic::catch_unwind(|| Dummy.notify());
+    let find = {
+        let _strict = strict_mode();
+        std::panic::catch_unwind(|| Dummy.find())
+    };
+
+    // Then both panic, rather than returning the value of the handler
+    assert!(notify.is_err());
+    assert!(find.is_err());
+}
+
+#[tokio::test]
+async fn lenient_mode_returns_default_values() {
+    // Given a trait annotated with `dummies(lenient)`
+    #[dummies(lenient)]
+    trait MyTrait {
+        fn answer(&self) -> i32;
+        fn name(&self) -> String;
+        fn pair(&self) -> Result<(u8, bool), String>;
+        fn answer_later(&self) -> impl Future<Output = i32>;
+    }
+
+    // When invoking its methods
+    let answer = Dummy.answer();
+    let name = Dummy.name();
+    let pair = Dummy.pair();
+    let answer_later = Dummy.answer_later().await;
+
+    // Then they return the `Default` values
+    assert_eq!(0, answer);
+    assert_eq!("", name);
+    assert_eq!(Ok((0, false)), pair);
+    assert_eq!(0, answer_later);
+}
+
+#[test]
+#[should_panic(expected = "not implemented: MyTrait::handle")]
+fn lenient_mode_panics_for_types_without_default() {
+    // Given a trait annotated with `dummies(lenient)` and a return type not implementing `Default`
+    struct NoDefault;
+
+    #[dummies(lenient)]
+    trait MyTrait {
+        fn handle(&self) -> NoDefault;
+    }
+
+    // When invoking the method
+    Dummy.handle();
+
+    // Then it panics, since there is no value to return
+}
+
+#[test]
+fn fill_missing_methods_of_impl() {
+    // Given a trait annotated with `dummies(fill)`
+    #[dummies(fill)]
+    trait MyTrait {
+        fn answer(&self) -> i32;
+        fn find(&self) -> Option<i32>;
+        fn notify(&self);
+    }
+
+    // When implementing only one of its methods for a stub annotated with `fill`
+    struct Stub;
+
+    #[double_trait::fill]
+    impl MyTrait for Stub {
+        fn answer(&self) -> i32 {
+            42
+        }
+    }
+
+    // Then the missing methods are generated, like they would have been for the dummy
+    assert_eq!(42, Stub.answer());
+    assert_eq!(None, Stub.find());
+    Stub.notify();
+    assert_eq!(None, Dummy.find());
+}
+
+#[test]
+fn fill_impl_of_generic_trait() {
+    // Given a generic trait annotated with `dummies(fill)`
+    #[dummies(fill)]
+    trait Get<T, const N: usize = 3> {
+        fn get(&self) -> Option<T>;
+        fn batch(&self) -> Vec<[T; N]>;
+        fn other(&self) -> i32;
+    }
+
+    // When implementing only one of its methods for a stub passing a concrete type
+    struct Stub;
+
+    #[double_trait::fill]
+    impl Get<i32> for Stub {
+        fn other(&self) -> i32 {
+            1
+        }
+    }
+
+    // Then the missing methods are generated with the generic arguments of the impl
+    let value: Option<i32> = Stub.get();
+    assert_eq!(None, value);
+    let batch: Vec<[i32; 3]> = Stub.batch();
+    assert!(batch.is_empty());
+    assert_eq!(1, Stub.other());
+}
+
+mod fill_across_modules {
+    pub mod production {
+        #[double_trait::dummies(fill)]
+        pub trait Store {
+            fn load(&self, key: &str) -> Option<String>;
+
+            #[dummies(strict)]
+            fn save(&mut self, key: &str, value: String);
+        }
+    }
+
+    use self::production::Store;
+
+    struct ReadOnlyStub;
+
+    #[double_trait::fill]
+    impl Store for ReadOnlyStub {
+        fn load(&self, _key: &str) -> Option<String> {
+            Some("value".to_owned())
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not implemented: Store::save")]
+    fn fill_impl_in_other_module() {
+        // Given a stub implemented for a trait from another module, which implements `load` only
+
+        // When invoking the filled in method, which is annotated with `dummies(strict)`
+        assert_eq!(Some("value".to_owned()), ReadOnlyStub.load("key"));
+        ReadOnlyStub.save("key", "value".to_owned());
+
+        // Then it panics
+    }
+}
+
+#[test]
+fn unsafe_trait_with_unsafe_method() {
+    // Given an unsafe trait, with an unsafe method, explicitly allowed to be implemented for the
+    // dummy
+    /// # Safety
+    ///
+    /// `id` must be unique.
+    #[dummies(allow_unsafe)]
+    unsafe trait RawDevice {
+        fn id(&self) -> Option<u32>;
+
+        /// # Safety
+        ///
+        /// `address` must be mapped.
+        unsafe fn write(&mut self, address: usize, value: u8);
+    }
+
+    // When invoking its methods
+    let id = Dummy.id();
+    // SAFETY: The dummy does not access any memory.
+    unsafe { Dummy.write(0x10, 42) };
+
+    // Then they behave like those of a safe trait
+    assert_eq!(None, id);
+}
+
+#[test]
+fn lending_iterator_with_generic_associated_type() {
+    // Given a lending iterator style trait with a generic associated type
+    #[dummies]
+    trait LendingIterator {
+        type Item<'a>: AsRef<[u8]>
+        where
+            Self: 'a;
+
+        fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>;
+    }
+
+    // When iterating the dummy
+    let mut dummy = Dummy;
+    let item = dummy.next();
+
+    // Then it is empty and uses a type satisfying the bounds of the item
+    assert!(item.is_none());
+    let _: <Dummy as LendingIterator>::Item<'_> = &[1u8, 2, 3][..];
+}
+
+#[dummies]
+trait Parser {
+    type Output;
+    type Token: AsRef<str>;
+
+    fn parse(&self, input: &str) -> Self::Output;
+
+    fn try_parse(&self, input: &str) -> Result<Self::Output, String>;
+
+    fn tokens(&self, input: &str) -> impl Iterator<Item = Self::Token>;
+
+    fn parse_async(&self, input: &str) -> impl Future<Output = <Self as Parser>::Output>;
+}
+
+#[tokio::test]
+async fn dummy_returns_default_for_associated_types() {
+    // Given a trait whose methods return associated types of `Self`
+
+    // When invoking them on the dummy
+    let output = Dummy.parse("42");
+    let try_output = Dummy.try_parse("42");
+    let tokens: Vec<_> = Dummy.tokens("42").collect();
+    let async_output = Dummy.parse_async("42").await;
+
+    // Then they return default values, rather than panicking
+    assert_eq!(Dummy, output);
+    assert_eq!(Ok(Dummy), try_output);
+    assert!(tokens.is_empty());
+    assert_eq!(Dummy, async_output);
+}
+
+#[test]
+#[should_panic(expected = "not implemented: Parser::parse")]
+fn stub_panics_for_associated_types() {
+    // Given a stub with an associated type the trait knows nothing about
+    struct ParserStub;
+    impl Parser for ParserStub {
+        type Output = u32;
+        type Token = String;
+    }
+
+    // When invoking a method returning the associated type
+    ParserStub.parse("42");
+
+    // Then the default of the trait panics
+}
+
+#[dummies]
+trait Connection {
+    fn open(url: &str) -> Self
+    where
+        Self: Sized;
+
+    fn clone_box(&self) -> Box<Self>;
+
+    fn try_open(url: &str) -> Result<Self, String>
+    where
+        Self: Sized;
+
+    fn reconnect(&self) -> Option<Self>
+    where
+        Self: Sized;
+
+    fn pool(&self, size: usize) -> Vec<Self>
+    where
+        Self: Sized;
+}
+
+#[test]
+fn dummy_returns_itself_from_constructors() {
+    // Given a trait with methods returning `Self`
+
+    // When invoking them on the dummy
+    let opened = Dummy::open("db://");
+    let boxed = Dummy.clone_box();
+    let try_opened = Dummy::try_open("db://");
+    let reconnected = Dummy.reconnect();
+    let pool = Dummy.pool(3);
+
+    // Then they return the dummy, rather than panicking
+    assert_eq!(Dummy, opened);
+    assert_eq!(Box::new(Dummy), boxed);
+    assert_eq!(Ok(Dummy), try_opened);
+    assert_eq!(None, reconnected);
+    assert!(pool.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "not implemented: Connection::open")]
+fn stub_panics_for_constructors() {
+    // Given a stub relying on the defaults of the trait
+    struct ConnectionStub;
+    impl Connection for ConnectionStub {}
+
+    // When invoking a method returning `Self`
+    ConnectionStub::open("db://");
+
+    // Then the default of the trait panics
+}
+
+mod borrowed_return_types {
+    use double_trait::{Dummy, dummies};
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Config {
+        retries: u32,
+    }
+
+    #[dummies]
+    trait Repository {
+        fn name(&self) -> &str;
+
+        fn label<'a>(&'a self, prefix: &'a str) -> &'a str;
+
+        fn items(&self) -> &[u32];
+
+        fn items_mut<'a>(&'a mut self) -> &'a mut [u32];
+
+        fn find(&self, index: usize) -> Option<&u32>;
+
+        fn config(&self) -> &Config;
+
+        fn config_mut<'a>(&'a mut self) -> Result<&'a mut Config, String>;
+
+        fn me(&self) -> &Self;
+    }
+
+    #[test]
+    fn dummy_returns_borrowed_defaults() {
+        // Given a trait with accessors returning references, with elided and explicit lifetimes
+        let mut dummy = Dummy;
+
+        // When invoking them on the dummy
+        let name = dummy.name();
+        let label = dummy.label("prefix");
+        let items = dummy.items();
+        let found = dummy.find(0);
+        let config = dummy.config();
+        let me = dummy.me();
+
+        // Then they return empty or default values, rather than panicking
+        assert_eq!("", name);
+        assert_eq!("", label);
+        assert!(items.is_empty());
+        assert_eq!(None, found);
+        assert_eq!(&Config::default(), config);
+        assert_eq!(&Dummy, me);
+        assert!(dummy.items_mut().is_empty());
+        dummy.config_mut().unwrap().retries = 3;
+    }
+
+    #[test]
+    #[should_panic(expected = "not implemented: Repository::config")]
+    fn stub_panics_for_references() {
+        // Given a stub relying on the defaults of the trait
+        struct RepositoryStub;
+        impl Repository for RepositoryStub {}
+
+        // When invoking a method returning a reference
+        let name = RepositoryStub.name();
+        assert_eq!("", name);
+        RepositoryStub.config();
+
+        // Then the trait can not borrow from anything and panics
+    }
+}
+
+mod smart_pointers {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    use double_trait::{Dummy, dummies};
+
+    #[derive(Debug, Default, PartialEq)]
+    pub struct Config;
+
+    #[dummies]
+    trait Cache {
+        fn first(&self) -> Box<Option<u8>>;
+
+        fn config(&self) -> Arc<Option<Config>>;
+
+        fn shared(&self) -> Rc<Self>;
+
+        fn label(&self) -> Cow<'_, str>;
+
+        fn items<'a>(&'a self) -> Cow<'a, [u32]>;
+
+        fn owned(&self) -> Cow<'static, Option<u32>>;
+
+        fn load(&self) -> impl Future<Output = Result<Arc<Vec<u8>>, String>>;
+    }
+
+    #[tokio::test]
+    async fn smart_pointers_wrap_the_default_of_their_pointee() {
+        // Given a trait with methods returning smart pointers
+
+        // When invoking them on the dummy
+        let first = Dummy.first();
+        let config = Dummy.config();
+        let shared = Dummy.shared();
+        let label = Dummy.label();
+        let items = Dummy.items();
+        let owned = Dummy.owned();
+        let loaded = Dummy.load().await;
+
+        // Then they wrap the default of their pointee, rather than panicking
+        assert_eq!(None, *first);
+        assert_eq!(None, *config);
+        assert_eq!(Dummy, *shared);
+        assert!(matches!(label, Cow::Borrowed("")));
+        assert!(matches!(items, Cow::Borrowed([])));
+        assert!(matches!(owned, Cow::Owned(None)));
+        assert!(loaded.unwrap().is_empty());
+    }
+}
+
+mod optional_impl_trait {
+    use double_trait::{Dummy, dummies};
+
+    #[dummies]
+    trait Feed {
+        fn entries(&self) -> Option<impl Iterator<Item = String>>;
+
+        fn next_page(&self) -> Result<Option<impl Future<Output = Vec<u8>>>, String>;
+
+        fn nested(&self) -> Option<Option<impl Iterator<Item = u32>>>;
+    }
+
+    #[test]
+    fn option_wrapped_opaque_types_compile() {
+        // Given a trait with methods returning options of `impl Trait`
+
+        // When invoking them on the dummy
+        let entries = Dummy.entries();
+        let next_page = Dummy.next_page();
+        let nested = Dummy.nested();
+
+        // Then they return `None`
+        assert!(entries.is_none());
+        assert!(next_page.unwrap().is_none());
+        assert!(nested.is_none());
+    }
+}
+
+mod poll_based_traits {
+    use std::{
+        cmp::{self, Ordering},
+        ops::ControlFlow,
+        pin::Pin,
+        sync::atomic,
+        task::{Context, Poll, Waker},
+    };
+
+    use double_trait::{Dummy, dummies};
+
+    #[dummies]
+    trait RawStream {
+        type Item;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+    }
+
+    #[dummies(poll = ready)]
+    trait RawSink {
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), String>>;
+    }
+
+    #[dummies]
+    trait Visitor {
+        fn visit(&mut self, node: &str) -> ControlFlow<String>;
+
+        fn compare(&self, left: u32, right: u32) -> cmp::Ordering;
+    }
+
+    #[dummies]
+    trait Pending {
+        fn poll_items(&mut self) -> Poll<impl Iterator<Item = u8>>;
+    }
+
+    mod atomics {
+        use std::sync::atomic::Ordering;
+
+        use double_trait::dummies;
+
+        #[dummies]
+        pub trait Memory {
+            fn order(&self) -> Ordering;
+        }
+    }
+
+    #[test]
+    fn poll_is_pending_by_default() {
+        // Given a poll based trait
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // When polling the dummy
+        let poll = Pin::new(&mut Dummy).poll_next(&mut cx);
+
+        // Then it is pending
+        assert_eq!(Poll::Pending, poll);
+    }
+
+    #[test]
+    fn poll_is_ready_if_configured() {
+        // Given a poll based trait annotated with `dummies(poll = ready)`
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // When polling the dummy
+        let poll = Pin::new(&mut Dummy).poll_flush(&mut cx);
+
+        // Then it is ready with the default of the inner type
+        assert_eq!(Poll::Ready(Ok(())), poll);
+    }
+
+    #[test]
+    fn visitor_continues_and_compares_equal() {
+        // Given a visitor trait
+
+        // When invoking its methods on the dummy
+        let flow = Dummy.visit("node");
+        let ordering = Dummy.compare(1, 2);
+
+        // Then it continues and considers everything equal
+        assert_eq!(ControlFlow::Continue(()), flow);
+        assert_eq!(Ordering::Equal, ordering);
+    }
+
+    #[test]
+    fn pending_poll_of_impl_trait() {
+        // Given a method returning a `Poll` of `impl Iterator`
+
+        let mut dummy = Dummy;
+
+        // When polling the dummy
+        let poll = dummy.poll_items();
+
+        // Then it compiles and is pending
+        assert!(poll.is_pending());
+    }
+
+    #[test]
+    fn imported_atomic_ordering_is_not_mistaken_for_cmp_ordering() {
+        use self::atomics::Memory;
+
+        // Given a method returning an imported `std::sync::atomic::Ordering`
+
+        // When invoking it on the dummy
+        let order = std::panic::catch_unwind(|| Dummy.order());
+
+        // Then it compiles and is unimplemented
+        assert!(order.is_err());
+        let _: fn(&Dummy) -> atomic::Ordering = <Dummy as Memory>::order;
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{self, Read};
+
+use double_trait::{Dummy, remote};
+
+#[test]
+fn override_method_of_foreign_trait() {
+    // Given a restated declaration of `std::io::Read`
+    remote! {
+        trait std::io::Read {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+        }
+    }
+
+    // When implementing the helper trait and using the wrapper as `Read`
+    struct Hello;
+
+    impl DoubleRead for Hello {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            buf[..5].copy_from_slice(b"Hello");
+            Ok(5)
+        }
+    }
+
+    let mut buf = [0; 5];
+    RemoteRead(Hello).read_exact(&mut buf).unwrap();
+
+    // Then the foreign trait forwards to the helper trait
+    assert_eq!(b"Hello", &buf);
+}
+
+#[test]
+fn pass_arguments_to_dummies() {
+    // Given a restated declaration of `std::io::Read` with lenient defaults
+    remote! {
+        #[dummies(lenient)]
+        trait std::io::Read {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+        }
+    }
+
+    // When reading from the dummy
+    let mut text = String::new();
+    let bytes_read = RemoteRead(Dummy).read_to_string(&mut text).unwrap();
+
+    // Then it behaves like an empty reader
+    assert_eq!(0, bytes_read);
+}
+
+#[test]
+fn generic_foreign_trait_with_associated_type() {
+    mod foreign {
+        pub trait Service<Request> {
+            type Response;
+
+            fn call(&mut self, request: Request) -> Self::Response;
+
+            fn ready(&self) -> bool {
+                true
+            }
+        }
+    }
+
+    // Given a restated declaration of a generic foreign trait, renamed to avoid name clashes
+    remote! {
+        trait foreign::Service<Request> as Echo {
+            type Response;
+
+            fn call(&mut self, request: Request) -> Self::Response;
+        }
+    }
+
+    // When implementing the helper trait
+    struct EchoService;
+
+    impl DoubleEcho<String> for EchoService {
+        type Response = String;
+
+        fn call(&mut self, request: String) -> String {
+            request
+        }
+    }
+
+    // Then the wrapper implements the foreign trait
+    use foreign::Service as _;
+    let mut service = RemoteEcho(EchoService);
+    assert!(service.ready());
+    assert_eq!("Hello", service.call("Hello".to_owned()));
+}
+
+#[test]
+fn unsafe_foreign_trait() {
+    mod foreign {
+        /// # Safety
+        ///
+        /// Must always report the same size.
+        pub unsafe trait Sized {
+            fn size(&self) -> usize;
+        }
+    }
+
+    // Given a restated declaration of an unsafe foreign trait
+    remote! {
+        /// # Safety
+        ///
+        /// Must always report the same size.
+        #[dummies(allow_unsafe, lenient)]
+        unsafe trait foreign::Sized {
+            fn size(&self) -> usize;
+        }
+    }
+
+    // When using the dummy wrapped as the foreign trait
+    use foreign::Sized as _;
+    let size = RemoteSized(Dummy).size();
+
+    // Then the wrapper implements it
+    assert_eq!(0, size);
+}
@@ -0,0 +1,23 @@
+//! Compile fail tests for the diagnostics emitted by `dummies`. Run with `TRYBUILD=overwrite` to
+//! update the expected output after changing a message. Cases in `tests/ui/pass` must compile in a
+//! crate of their own.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
+
+#[test]
+#[cfg(not(feature = "stream"))]
+fn ui_without_stream_feature() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/without_stream/*.rs");
+}
+
+#[test]
+#[cfg(feature = "stream")]
+fn ui_pass_with_stream_feature() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+}
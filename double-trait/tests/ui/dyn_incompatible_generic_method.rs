@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies(dyn_compatible)]
+trait MyTrait {
+    fn convert<T>(&self, value: T) -> T;
+}
+
+fn main() {}
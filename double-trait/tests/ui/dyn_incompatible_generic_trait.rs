@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies(dyn_compatible)]
+trait MyTrait<T> {
+    fn answer(&self) -> T;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies(dyn_compatible)]
+trait MyTrait {
+    fn new() -> Self;
+}
+
+fn main() {}
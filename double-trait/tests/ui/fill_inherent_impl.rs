@@ -0,0 +1,6 @@
+struct Stub;
+
+#[double_trait::fill]
+impl Stub {}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Within this crate `futures_util` names `core` rather than the actual crate, just as if it would
+// not depend on `futures-util`. Code generated by `dummies` must not refer to it by its name.
+extern crate core as futures_util;
+
+use double_trait::{Dummy, dummies};
+
+// Stand in for a reexport of `Stream` by a crate other than `futures-util`.
+use double_trait::__private::futures_util::Stream;
+
+#[dummies]
+trait MyTrait {
+    fn answer(&self) -> impl Stream<Item = i32>;
+
+    async fn ping(&self) -> Option<u8>;
+}
+
+fn main() {
+    let _stream = Dummy.answer();
+    let _ping = Dummy.ping();
+}
@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies]
+unsafe trait RawDevice {
+    fn id(&self) -> u32;
+}
+
+fn main() {}
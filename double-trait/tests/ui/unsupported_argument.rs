@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies(foobar)]
+trait MyTrait {
+    fn answer(&self) -> i32;
+}
+
+fn main() {}
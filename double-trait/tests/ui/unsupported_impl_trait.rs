@@ -0,0 +1,8 @@
+use double_trait::dummies;
+
+#[dummies]
+trait MyTrait {
+    fn display(&self) -> impl std::fmt::Display;
+}
+
+fn main() {}
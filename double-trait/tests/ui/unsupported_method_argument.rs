@@ -0,0 +1,9 @@
+use double_trait::dummies;
+
+#[dummies]
+trait MyTrait {
+    #[dummies(verbose)]
+    fn answer(&self) -> i32;
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use double_trait::dummies;
+
+// Stand in for `futures_util::Stream`, which is not available without the `stream` feature.
+trait Stream {
+    type Item;
+}
+
+#[dummies]
+trait MyTrait {
+    fn answer(&self) -> impl Stream<Item = i32>;
+}
+
+fn main() {}